@@ -0,0 +1,155 @@
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Stops the calls of a client from another thread.
+///
+/// A client given a token with `DockerClient::with_cancellation` checks it
+/// throughout every call, so cancelling the token ends a followed log,
+/// attach or wait which would otherwise block until the container stops.
+/// Calls in flight and any made afterwards fail with
+/// `DockerError::Cancelled`. Clones share the token.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<(Mutex<bool>, Condvar)>,
+}
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        let (cancelled, changed) = &*self.state;
+        if let Ok(mut cancelled) = cancelled.lock() {
+            *cancelled = true;
+        }
+        changed.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        let (cancelled, _) = &*self.state;
+        cancelled.lock().map_or(true, |cancelled| *cancelled)
+    }
+
+    /// Waits for `duration` or until the token is cancelled, whichever comes
+    /// first, and returns whether it was cancelled.
+    pub(crate) fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        let (cancelled, changed) = &*self.state;
+        let mut cancelled = match cancelled.lock() {
+            Ok(cancelled) => cancelled,
+            Err(_) => return true,
+        };
+        while !*cancelled {
+            let left = match deadline.checked_duration_since(Instant::now()) {
+                Some(left) if left > Duration::ZERO => left,
+                _ => return false,
+            };
+            cancelled = match changed.wait_timeout(cancelled, left) {
+                Ok((cancelled, _)) => cancelled,
+                Err(_) => return true,
+            };
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::cancellation::CancellationToken;
+    #[cfg(all(unix, feature = "curl"))]
+    use crate::client::transport::CurlTransport;
+    #[cfg(unix)]
+    use crate::client::transport::{Transport, UnixTransport};
+    #[cfg(unix)]
+    use crate::client::version::MAX_API_VERSION;
+    #[cfg(unix)]
+    use crate::client::DockerClient;
+    #[cfg(unix)]
+    use crate::container::logs::LogsOptions;
+    #[cfg(unix)]
+    use crate::error::DockerError;
+    #[cfg(unix)]
+    use crate::output::Discard;
+    #[cfg(unix)]
+    use std::io::{Read, Write};
+    #[cfg(unix)]
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn sleep_ends_early_once_cancelled() {
+        let token = CancellationToken::new();
+        assert!(!token.sleep(Duration::from_millis(10)));
+
+        let canceller = token.clone();
+        let started = Instant::now();
+        let handle = thread::spawn(move || canceller.cancel());
+        assert!(token.sleep(Duration::from_secs(30)));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(token.is_cancelled());
+        handle.join().unwrap();
+    }
+
+    /// Follows the logs of a container which never writes anything, and
+    /// cancels them from another thread.
+    #[cfg(unix)]
+    fn cancels_a_followed_stream<T: Transport + 'static>(name: &str, transport: T) {
+        let socket = std::env::temp_dir().join(format!(
+            "dockurl-cancel-{}-{}.sock",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n")
+                .unwrap();
+            // Hold the connection open until the client goes away.
+            let _ = stream.read(&mut request);
+        });
+
+        let token = CancellationToken::new();
+        let client = DockerClient::new("localhost", false)
+            .with_unix_socket(&socket)
+            .with_transport(transport)
+            .with_api_version(MAX_API_VERSION)
+            .with_cancellation(token.clone());
+        let mut options = LogsOptions::new();
+        options.follow(true);
+        options.tty(false);
+        let tail =
+            thread::spawn(move || client.get_container_logs_with_options("abc", options, Discard));
+
+        thread::sleep(Duration::from_millis(100));
+        let cancelled = Instant::now();
+        token.cancel();
+        match tail.join().unwrap() {
+            Err(DockerError::Cancelled(request)) => assert_eq!(
+                request,
+                "GET /containers/abc/logs?follow=1&stdout=1&stderr=1&timestamps=0&tail=all"
+            ),
+            other => panic!("expected a cancellation, got {:?}", other),
+        }
+        assert!(cancelled.elapsed() < Duration::from_secs(5));
+
+        server.join().unwrap();
+        std::fs::remove_file(socket).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "curl"))]
+    fn cancels_streams_over_curl() {
+        cancels_a_followed_stream("curl", CurlTransport::new());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn cancels_streams_over_the_unix_transport() {
+        cancels_a_followed_stream("unix", UnixTransport::new());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::client::host::DockerHost;
+use crate::client::tls::TlsConfig;
+use crate::client::DEFAULT_UNIX_SOCKET;
+use crate::error::DockerError::ContextNotFoundError;
+use crate::error::{DockerError, DockerResult};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// The context the Docker CLI uses when none has been selected; it stands for
+/// `DOCKER_HOST` or the default socket and has no metadata on disk.
+pub const DEFAULT_CONTEXT: &str = "default";
+
+/// A named endpoint created with `docker context create`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DockerContext {
+    pub name: String,
+    pub host: DockerHost,
+    pub tls: Option<TlsConfig>,
+}
+impl DockerContext {
+    /// Loads the context called `name` from `config_dir` (usually
+    /// `~/.docker`).
+    ///
+    /// Metadata lives in `contexts/meta/<id>/meta.json` and TLS material in
+    /// `contexts/tls/<id>/docker/`, where `<id>` is a digest of the name. The
+    /// meta directories are searched by name rather than recomputing it, and
+    /// those which cannot be read are skipped with a warning, so a broken
+    /// context does not hide the others. `DEFAULT_CONTEXT` always resolves to
+    /// the default socket.
+    pub fn load<P: AsRef<Path>>(config_dir: P, name: &str) -> DockerResult<Self> {
+        if name == DEFAULT_CONTEXT {
+            return Ok(Self {
+                name: name.to_string(),
+                host: DockerHost::Unix(PathBuf::from(DEFAULT_UNIX_SOCKET)),
+                tls: None,
+            });
+        }
+
+        let contexts_dir = config_dir.as_ref().join("contexts");
+        let entries = match fs::read_dir(contexts_dir.join("meta")) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(ContextNotFoundError(name.to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        for entry in entries.flatten() {
+            let meta_file = entry.path().join("meta.json");
+            if !meta_file.is_file() {
+                continue;
+            }
+            let meta = fs::read(&meta_file)
+                .map_err(DockerError::from)
+                .and_then(|bytes| Ok(serde_json::from_slice::<ContextMeta>(&bytes)?));
+            let meta = match meta {
+                Ok(meta) => meta,
+                Err(e) => {
+                    log::warn!(target: "dockurl", "Skipping context {}: {}", meta_file.display(), e);
+                    continue;
+                }
+            };
+            if meta.name != name {
+                continue;
+            }
+
+            let endpoint = meta.endpoints.get("docker");
+            let host = match endpoint.and_then(|endpoint| endpoint.host.as_deref()) {
+                Some(host) => DockerHost::parse(host)?,
+                None => return Err(ContextNotFoundError(name.to_string())),
+            };
+            let verify = !endpoint.is_some_and(|endpoint| endpoint.skip_tls_verify);
+
+            let tls_dir = contexts_dir
+                .join("tls")
+                .join(entry.file_name())
+                .join("docker");
+            let has_client_cert =
+                tls_dir.join("cert.pem").is_file() || tls_dir.join("key.pem").is_file();
+            let tls = match &host {
+                DockerHost::Tcp { .. } if has_client_cert => {
+                    Some(TlsConfig::from_cert_path(tls_dir, verify)?)
+                }
+                DockerHost::Tcp { .. } if tls_dir.is_dir() => Some(TlsConfig {
+                    ca_cert: Some(tls_dir.join("ca.pem")).filter(|path| path.is_file()),
+                    verify,
+                    ..TlsConfig::default()
+                }),
+                DockerHost::Tcp { tls: true, .. } => Some(TlsConfig {
+                    verify,
+                    ..TlsConfig::default()
+                }),
+                _ => None,
+            };
+
+            return Ok(Self {
+                name: meta.name,
+                host,
+                tls,
+            });
+        }
+
+        Err(ContextNotFoundError(name.to_string()))
+    }
+
+    /// Reads `currentContext` from `config.json` in `config_dir`, which is
+    /// what `docker context use` writes. Returns `None` when no context has
+    /// been selected.
+    pub fn current_name<P: AsRef<Path>>(config_dir: P) -> DockerResult<Option<String>> {
+        let config_file = config_dir.as_ref().join("config.json");
+        let bytes = match fs::read(&config_file) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let config: ConfigFile = serde_json::from_slice(&bytes)?;
+
+        Ok(config
+            .current_context
+            .filter(|name| !name.is_empty() && name != DEFAULT_CONTEXT))
+    }
+}
+
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(rename = "currentContext", default)]
+    current_context: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContextMeta {
+    name: String,
+    #[serde(default)]
+    endpoints: HashMap<String, EndpointMeta>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EndpointMeta {
+    host: Option<String>,
+    #[serde(rename = "SkipTLSVerify", default)]
+    skip_tls_verify: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::context::DockerContext;
+    use crate::client::host::DockerHost;
+    use crate::client::tls::TlsConfig;
+    use crate::error::DockerError;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// Lays out a Docker CLI config directory with a `remote` TLS context, a
+    /// `rootless` socket context, a context whose metadata is cut short and
+    /// `remote` selected as current.
+    fn fixture(test: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dockurl-context-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let write = |path: &Path, contents: &str| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        };
+        write(&dir.join("config.json"), r#"{"currentContext":"remote"}"#);
+        write(
+            &dir.join("contexts/meta/0a1b/meta.json"),
+            r#"{"Name":"remote","Metadata":{},"Endpoints":{"docker":{"Host":"tcp://10.0.0.5:2376","SkipTLSVerify":false}}}"#,
+        );
+        for file in &["ca.pem", "cert.pem", "key.pem"] {
+            write(&dir.join("contexts/tls/0a1b/docker").join(file), "");
+        }
+        write(&dir.join("contexts/meta/1f2e/meta.json"), r#"{"Name":"#);
+        write(
+            &dir.join("contexts/meta/2c3d/meta.json"),
+            r#"{"Name":"rootless","Metadata":{},"Endpoints":{"docker":{"Host":"unix:///run/user/1000/docker.sock","SkipTLSVerify":false}}}"#,
+        );
+
+        dir
+    }
+
+    #[test]
+    fn loads_tcp_context_with_tls_material() {
+        let dir = fixture("tcp");
+        let context = DockerContext::load(&dir, "remote").unwrap();
+        let tls_dir = dir.join("contexts/tls/0a1b/docker");
+
+        assert_eq!(
+            context.host,
+            DockerHost::Tcp {
+                address: "10.0.0.5:2376".to_string(),
+                tls: false
+            }
+        );
+        assert_eq!(
+            context.tls,
+            Some(TlsConfig::new(
+                tls_dir.join("ca.pem"),
+                tls_dir.join("cert.pem"),
+                tls_dir.join("key.pem")
+            ))
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn loads_unix_context_and_current_name() {
+        let dir = fixture("unix");
+
+        assert_eq!(
+            DockerContext::current_name(&dir).unwrap(),
+            Some("remote".to_string())
+        );
+        let context = DockerContext::load(&dir, "rootless").unwrap();
+        assert_eq!(
+            context.host,
+            DockerHost::Unix(PathBuf::from("/run/user/1000/docker.sock"))
+        );
+        assert!(context.tls.is_none());
+        assert!(matches!(
+            DockerContext::load(&dir, "missing"),
+            Err(DockerError::ContextNotFoundError(name)) if name == "missing"
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,138 @@
+use crate::client::DEFAULT_UNIX_SOCKET;
+use crate::error::DockerError::{InvalidDockerHostError, UnsupportedDockerHostError};
+use crate::error::DockerResult;
+use std::path::PathBuf;
+
+/// The port a plain-text TCP daemon listens on by default.
+pub const DEFAULT_HTTP_PORT: u16 = 2375;
+/// The port a TLS protected TCP daemon listens on by default.
+pub const DEFAULT_HTTPS_PORT: u16 = 2376;
+
+/// A daemon endpoint as written in `DOCKER_HOST` or `docker -H`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DockerHost {
+    Unix(PathBuf),
+    /// `address` is always `host:port`; `tls` is set for `https://` URLs.
+    Tcp {
+        address: String,
+        tls: bool,
+    },
+}
+impl DockerHost {
+    /// Parses `unix://`, `tcp://`, `http://` and `https://` URLs; a bare
+    /// `host:port` is treated as `tcp://`. Named pipes and SSH are rejected
+    /// since curl has no way of reaching them.
+    pub fn parse(host: &str) -> DockerResult<Self> {
+        let host = host.trim();
+        let (scheme, rest) = match host.find("://") {
+            Some(index) => (&host[..index], &host[index + 3..]),
+            None => ("tcp", host),
+        };
+
+        match scheme.to_ascii_lowercase().as_str() {
+            "unix" => {
+                if rest.is_empty() {
+                    Ok(DockerHost::Unix(PathBuf::from(DEFAULT_UNIX_SOCKET)))
+                } else {
+                    Ok(DockerHost::Unix(PathBuf::from(rest)))
+                }
+            }
+            "tcp" | "http" => Ok(DockerHost::Tcp {
+                address: parse_address(host, rest, DEFAULT_HTTP_PORT)?,
+                tls: false,
+            }),
+            "https" => Ok(DockerHost::Tcp {
+                address: parse_address(host, rest, DEFAULT_HTTPS_PORT)?,
+                tls: true,
+            }),
+            "npipe" | "ssh" | "fd" => Err(UnsupportedDockerHostError(host.to_string())),
+            _ => Err(InvalidDockerHostError(host.to_string())),
+        }
+    }
+}
+
+/// Normalizes the authority of a TCP host to `host:port`, defaulting the host
+/// to `localhost` and the port to `default_port`.
+fn parse_address(host: &str, rest: &str, default_port: u16) -> DockerResult<String> {
+    let authority = match rest.find('/') {
+        Some(index) if rest[index..].trim_end_matches('/').is_empty() => &rest[..index],
+        Some(_) => return Err(InvalidDockerHostError(host.to_string())),
+        None => rest,
+    };
+
+    // The port is whatever follows the last colon, unless that colon belongs
+    // to a bracketed IPv6 literal.
+    let (name, port) = match authority.rfind(':') {
+        Some(index) if !authority[index..].contains(']') => {
+            (&authority[..index], Some(&authority[index + 1..]))
+        }
+        _ => (authority, None),
+    };
+    let name = if name.is_empty() { "localhost" } else { name };
+    let port = match port {
+        Some(port) => port
+            .parse::<u16>()
+            .map_err(|_| InvalidDockerHostError(host.to_string()))?,
+        None => default_port,
+    };
+
+    Ok(format!("{}:{}", name, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::host::DockerHost;
+    use std::path::PathBuf;
+
+    fn tcp(address: &str, tls: bool) -> DockerHost {
+        DockerHost::Tcp {
+            address: address.to_string(),
+            tls,
+        }
+    }
+
+    #[test]
+    fn parses_supported_schemes() {
+        assert_eq!(
+            DockerHost::parse("unix:///run/user/1000/docker.sock").unwrap(),
+            DockerHost::Unix(PathBuf::from("/run/user/1000/docker.sock"))
+        );
+        assert_eq!(
+            DockerHost::parse("unix://").unwrap(),
+            DockerHost::Unix(PathBuf::from("/var/run/docker.sock"))
+        );
+        assert_eq!(
+            DockerHost::parse("tcp://10.0.0.5:2375").unwrap(),
+            tcp("10.0.0.5:2375", false)
+        );
+        assert_eq!(
+            DockerHost::parse("tcp://docker.example.com").unwrap(),
+            tcp("docker.example.com:2375", false)
+        );
+        assert_eq!(
+            DockerHost::parse("http://localhost:8080/").unwrap(),
+            tcp("localhost:8080", false)
+        );
+        assert_eq!(
+            DockerHost::parse("https://[::1]").unwrap(),
+            tcp("[::1]:2376", true)
+        );
+        assert_eq!(
+            DockerHost::parse("tcp://").unwrap(),
+            tcp("localhost:2375", false)
+        );
+        assert_eq!(
+            DockerHost::parse("10.0.0.5:2375").unwrap(),
+            tcp("10.0.0.5:2375", false)
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_and_invalid_hosts() {
+        assert!(DockerHost::parse("npipe:////./pipe/docker_engine").is_err());
+        assert!(DockerHost::parse("ssh://user@host").is_err());
+        assert!(DockerHost::parse("gopher://host").is_err());
+        assert!(DockerHost::parse("tcp://host:port").is_err());
+        assert!(DockerHost::parse("tcp://host:2375/base").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+/// Reassembles the newline delimited lines of a JSON-lines stream, such as
+/// build and pull progress, from the arbitrary chunks a transport hands to
+/// a handler. A line, or a UTF-8 sequence within it, may be split across any
+/// number of writes.
+#[derive(Debug, Default)]
+pub(crate) struct LineDecoder {
+    pending: Vec<u8>,
+    /// How much of `pending` has already been handed out as lines.
+    consumed: usize,
+}
+impl LineDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffers `data` until `next_line` hands out the lines it completes.
+    pub fn push(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+    }
+
+    /// The next complete line, without its `\n` or `\r\n` terminator. Bytes
+    /// which are not valid UTF-8 are replaced rather than dropping the line.
+    pub fn next_line(&mut self) -> Option<String> {
+        match self.pending[self.consumed..]
+            .iter()
+            .position(|&b| b == b'\n')
+        {
+            Some(end) => {
+                let line = &self.pending[self.consumed..self.consumed + end];
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                let line = String::from_utf8_lossy(line).into_owned();
+                self.consumed += end + 1;
+                Some(line)
+            }
+            None => {
+                self.pending.drain(..self.consumed);
+                self.consumed = 0;
+                None
+            }
+        }
+    }
+
+    /// Hands out whatever follows the last newline once the stream has ended.
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = &self.pending[self.consumed..];
+        let line = if rest.is_empty() {
+            None
+        } else {
+            Some(String::from_utf8_lossy(rest.strip_suffix(b"\r").unwrap_or(rest)).into_owned())
+        };
+        self.pending.clear();
+        self.consumed = 0;
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::line_decoder::LineDecoder;
+
+    const FIXTURE: &str = "{\"stream\":\"Step 1/2 : FROM alpine\\n\"}\r\n\
+                           {\"stream\":\" ---\\u003e Using cache \u{2713}\\n\"}\r\n\
+                           \r\n\
+                           {\"aux\":{\"ID\":\"sha256:e821df6f41ad\"}}\n\
+                           {\"error\":\"unfinished";
+
+    fn decode(chunks: &[&[u8]]) -> Vec<String> {
+        let mut decoder = LineDecoder::new();
+        let mut lines = vec![];
+        for chunk in chunks {
+            decoder.push(chunk);
+            while let Some(line) = decoder.next_line() {
+                lines.push(line);
+            }
+        }
+        lines.extend(decoder.finish());
+        lines
+    }
+
+    #[test]
+    fn splits_lines_and_strips_terminators() {
+        assert_eq!(
+            decode(&[FIXTURE.as_bytes()]),
+            vec![
+                "{\"stream\":\"Step 1/2 : FROM alpine\\n\"}",
+                "{\"stream\":\" ---\\u003e Using cache \u{2713}\\n\"}",
+                "",
+                "{\"aux\":{\"ID\":\"sha256:e821df6f41ad\"}}",
+                "{\"error\":\"unfinished",
+            ]
+        );
+    }
+
+    #[test]
+    fn reassembles_lines_split_at_every_offset() {
+        let bytes = FIXTURE.as_bytes();
+        let expected = decode(&[bytes]);
+        for first in 0..=bytes.len() {
+            assert_eq!(decode(&[&bytes[..first], &bytes[first..]]), expected);
+            for second in first..=bytes.len() {
+                assert_eq!(
+                    decode(&[&bytes[..first], &bytes[first..second], &bytes[second..]]),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reassembles_lines_fed_a_byte_at_a_time() {
+        let bytes = FIXTURE.as_bytes();
+        let chunks: Vec<&[u8]> = bytes.chunks(1).collect();
+        assert_eq!(decode(&chunks), decode(&[bytes]));
+    }
+}
@@ -0,0 +1,797 @@
+pub mod cancellation;
+pub mod context;
+pub mod host;
+pub(crate) mod line_decoder;
+pub mod replay;
+pub(crate) mod request;
+pub mod retry;
+pub mod tls;
+pub mod transport;
+pub(crate) mod url;
+pub mod version;
+mod version_handler;
+
+use crate::client::cancellation::CancellationToken;
+use crate::client::context::{DockerContext, DEFAULT_CONTEXT};
+use crate::client::host::DockerHost;
+use crate::client::replay::{Chunk, Exchange, Fixture, Recorder, Replay};
+use crate::client::request::{Request, Response};
+use crate::client::retry::RetryPolicy;
+use crate::client::tls::TlsConfig;
+#[cfg(feature = "curl")]
+use crate::client::transport::CurlTransport;
+#[cfg(all(unix, not(feature = "curl")))]
+use crate::client::transport::UnixTransport;
+use crate::client::transport::{HttpRequest, ResponseSink, Transport};
+use crate::error::DockerError::{Cancelled, ContextNotFoundError, RetriesExhaustedError};
+use crate::error::{DockerError, DockerResult};
+use crate::output::ResponseHandler;
+use std::cell::Cell;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// The socket the Docker daemon listens on by default.
+pub const DEFAULT_UNIX_SOCKET: &str = "/var/run/docker.sock";
+
+/// A reusable handle to a Docker daemon.
+///
+/// `DockerClient` owns the endpoint configuration so that it does not have to
+/// be threaded through every call. Container, image and network operations are
+/// exposed as methods; the free functions in `container`, `image` and
+/// `network` are thin wrappers which build a client for a single call.
+///
+/// Requests go over a `Transport`, `CurlTransport` unless another is set
+/// with `with_transport` or the `curl` feature is disabled, in which case
+/// it is `UnixTransport`. Clones share their transport, and with it the
+/// curl transport's pool of keep-alive connections, and a client can be used
+/// from several threads at once.
+///
+/// Since clones are cheap, per-call settings are applied to a clone:
+/// `client.clone().with_timeout(Duration::from_secs(5)).stop_container(..)`.
+///
+/// Request paths are prefixed with an API version such as `/v1.41`. Unless
+/// one is pinned with `with_api_version` or `DOCKER_API_VERSION`, it is
+/// negotiated with the daemon on the first request which needs it, and
+/// shared by clones from then on.
+#[derive(Debug, Clone)]
+pub struct DockerClient {
+    docker_host: String,
+    unix_socket: Option<PathBuf>,
+    tls: Option<TlsConfig>,
+    /// The pinned or negotiated version, shared between clones.
+    api_version: Arc<Mutex<Option<String>>>,
+    api_version_pinned: bool,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    long_running_timeout: Option<Duration>,
+    retry_policy: Option<RetryPolicy>,
+    recorder: Option<Recorder>,
+    cancellation: Option<CancellationToken>,
+    transport: Arc<dyn Transport>,
+}
+impl DockerClient {
+    /// Creates a client for `docker_host`. When `use_unix_socket` is set,
+    /// requests are sent over the socket given by `resolve_unix_socket`.
+    pub fn new(docker_host: &str, use_unix_socket: bool) -> Self {
+        Self {
+            docker_host: docker_host.to_string(),
+            unix_socket: if use_unix_socket {
+                Some(resolve_unix_socket())
+            } else {
+                None
+            },
+            tls: None,
+            api_version: Arc::new(Mutex::new(None)),
+            api_version_pinned: false,
+            connect_timeout: None,
+            timeout: None,
+            long_running_timeout: None,
+            retry_policy: None,
+            recorder: None,
+            cancellation: None,
+            transport: default_transport(),
+        }
+    }
+
+    /// Creates a client for an endpoint parsed from `DOCKER_HOST`.
+    pub fn from_host(host: DockerHost) -> Self {
+        match host {
+            DockerHost::Unix(path) => Self::new("localhost", false).with_unix_socket(path),
+            DockerHost::Tcp { address, tls } => {
+                let client = Self::new(&address, false);
+                if tls {
+                    client.with_tls(TlsConfig {
+                        verify: true,
+                        ..TlsConfig::default()
+                    })
+                } else {
+                    client
+                }
+            }
+        }
+    }
+
+    /// Creates a client the way the Docker CLI resolves its endpoint from the
+    /// environment: `DOCKER_HOST` picks the daemon, with `DOCKER_TLS_VERIFY`
+    /// enabling TLS for TCP hosts using the certificates in
+    /// `DOCKER_CERT_PATH`. Without `DOCKER_HOST`, the
+    /// context named by `DOCKER_CONTEXT` or `currentContext` in the CLI's
+    /// `config.json` is used, falling back to `DEFAULT_UNIX_SOCKET`.
+    /// `DOCKER_API_VERSION` pins the API version in every case.
+    pub fn from_env() -> DockerResult<Self> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Creates a client for the Docker context called `name`, as stored in
+    /// `DOCKER_CONFIG` or `~/.docker`.
+    pub fn from_context(name: &str) -> DockerResult<Self> {
+        let var = |name: &str| env::var(name).ok();
+        match docker_config_dir(&var) {
+            Some(config_dir) => Self::from_context_in(config_dir, name),
+            None => Err(ContextNotFoundError(name.to_string())),
+        }
+    }
+
+    /// Creates a client for the Docker context called `name` stored under
+    /// `config_dir`.
+    pub fn from_context_in<P: AsRef<Path>>(config_dir: P, name: &str) -> DockerResult<Self> {
+        let context = DockerContext::load(config_dir, name)?;
+        let client = Self::from_host(context.host);
+
+        Ok(match context.tls {
+            Some(tls) => client.with_tls(tls),
+            None => client,
+        })
+    }
+
+    fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> DockerResult<Self> {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+
+        let mut client = match var("DOCKER_HOST") {
+            Some(host) => {
+                let host = DockerHost::parse(&host)?;
+                let is_tcp = matches!(host, DockerHost::Tcp { .. });
+                let mut client = Self::from_host(host);
+
+                // Like the Docker CLI, only `DOCKER_TLS_VERIFY` (or an
+                // `https://` host) turns TLS on; `DOCKER_CERT_PATH` merely
+                // says where the certificates are.
+                let tls_verify = var("DOCKER_TLS_VERIFY").is_some();
+                if is_tcp && (client.tls.is_some() || tls_verify) {
+                    let tls = match var("DOCKER_CERT_PATH") {
+                        Some(cert_path) => TlsConfig::from_cert_path(cert_path, true)?,
+                        // The CLI's config directory is only where certificates
+                        // are looked for by default, and need not hold any.
+                        None => docker_config_dir(&var)
+                            .and_then(|dir| TlsConfig::from_cert_path(dir, true).ok())
+                            .unwrap_or(TlsConfig {
+                                verify: true,
+                                ..TlsConfig::default()
+                            }),
+                    };
+                    client = client.with_tls(tls);
+                }
+                client
+            }
+            None => match docker_config_dir(&var) {
+                Some(config_dir) => {
+                    let name = match var("DOCKER_CONTEXT") {
+                        Some(name) => Some(name),
+                        None => DockerContext::current_name(&config_dir)?,
+                    };
+                    Self::from_context_in(config_dir, name.as_deref().unwrap_or(DEFAULT_CONTEXT))?
+                }
+                None => Self::from_host(DockerHost::Unix(PathBuf::from(DEFAULT_UNIX_SOCKET))),
+            },
+        };
+
+        if let Some(api_version) = var("DOCKER_API_VERSION") {
+            client = client.with_api_version(&api_version);
+        }
+
+        Ok(client)
+    }
+
+    /// Sends requests over the Unix socket at `path` instead of TCP.
+    pub fn with_unix_socket<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.unix_socket = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Talks to the daemon over HTTPS, verifying it and authenticating with
+    /// the certificates in `tls`.
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Pins every request to version `api_version` (e.g. `1.40`) of the
+    /// Engine API instead of negotiating one with the daemon.
+    pub fn with_api_version(mut self, api_version: &str) -> Self {
+        self.api_version = Arc::new(Mutex::new(Some(
+            api_version.trim_start_matches('v').to_string(),
+        )));
+        self.api_version_pinned = true;
+        self
+    }
+
+    /// Limits how long establishing a connection to the daemon may take.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Limits how long a whole call may take, so that a wedged daemon cannot
+    /// hang it forever. Calls which stream for as long as the daemon keeps
+    /// working (`build_image`, `create_image`, `attach_to_container`,
+    /// `get_container_logs` and `wait_for_container_to_exit`) are exempt and
+    /// governed by `with_long_running_timeout` instead.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Limits how long a long-running call may take; unlimited by default.
+    pub fn with_long_running_timeout(mut self, timeout: Duration) -> Self {
+        self.long_running_timeout = Some(timeout);
+        self
+    }
+
+    /// Retries transient failures according to `retry_policy`. Calls are not
+    /// retried unless a policy is set.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Records every exchange with the daemon into `recorder`, which can
+    /// then be saved as a fixture for `with_replay`.
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Answers requests from the exchanges recorded in `fixture` instead of
+    /// contacting the daemon. A request with no recorded exchange left fails
+    /// with `NoRecordedResponseError`.
+    pub fn with_replay(self, fixture: Fixture) -> Self {
+        self.with_transport(Replay::new(fixture))
+    }
+
+    /// Makes every call fail with `DockerError::Cancelled` once `token` is
+    /// cancelled, including calls in flight. To stop a single long-running
+    /// call, such as a followed log, give the token to a clone of the client
+    /// and make the call on that.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Sends requests over `transport` instead of the default one.
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    pub fn docker_host(&self) -> &str {
+        &self.docker_host
+    }
+
+    pub fn use_unix_socket(&self) -> bool {
+        self.unix_socket.is_some()
+    }
+
+    pub fn unix_socket(&self) -> Option<&Path> {
+        self.unix_socket.as_deref()
+    }
+
+    pub fn tls(&self) -> Option<&TlsConfig> {
+        self.tls.as_ref()
+    }
+
+    /// The pinned version, or the negotiated one once a request has been
+    /// made.
+    pub fn api_version(&self) -> Option<String> {
+        self.api_version.lock().unwrap().clone()
+    }
+
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn long_running_timeout(&self) -> Option<Duration> {
+        self.long_running_timeout
+    }
+
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    /// Sends `request` to the daemon, feeding the response headers and body
+    /// to `handler`, and returns the response code along with the body of an
+    /// error response.
+    ///
+    /// When a retry policy applies to the request, transient failures are
+    /// retried with backoff. The body of a response which is going to be
+    /// retried is withheld from `handler`, and once the policy gives up the
+    /// error records how many attempts were made.
+    pub(crate) fn execute<H: ResponseHandler>(
+        &self,
+        request: &Request,
+        handler: &mut H,
+    ) -> DockerResult<Response> {
+        let api_version = self.api_version_for(request)?;
+        let api_version = api_version.as_deref();
+        let policy = match &self.retry_policy {
+            Some(policy) if policy.applies_to(request) => policy,
+            _ => {
+                return self
+                    .execute_once(request, api_version, handler, &[])
+                    .map(|attempt| attempt.response)
+                    .map_err(|(error, _)| error)
+            }
+        };
+
+        let mut attempt = 1;
+        loop {
+            let result = self.execute_once(request, api_version, handler, &policy.retry_on_status);
+            let error = match result {
+                Ok(Attempt {
+                    response,
+                    held: false,
+                }) => return Ok(response),
+                Ok(Attempt {
+                    response,
+                    held: true,
+                }) => request.error(&response),
+                Err((error @ Cancelled(_), _)) => return Err(error),
+                Err((error, delivered)) => {
+                    if delivered || !policy.retries_error(&error) {
+                        return Err(if attempt > 1 {
+                            RetriesExhaustedError(attempt, Box::new(error))
+                        } else {
+                            error
+                        });
+                    }
+                    error
+                }
+            };
+
+            if attempt >= policy.max_attempts {
+                return Err(RetriesExhaustedError(attempt, Box::new(error)));
+            }
+            match &self.cancellation {
+                Some(token) => {
+                    if token.sleep(policy.backoff(attempt)) {
+                        return Err(self.http_request(request, api_version).cancelled());
+                    }
+                }
+                None => thread::sleep(policy.backoff(attempt)),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Makes a single attempt at `request`. The body of a response whose code
+    /// is in `hold` is kept back from `handler`. Errors carry whether any of
+    /// the body had already reached `handler`.
+    fn execute_once<H: ResponseHandler>(
+        &self,
+        request: &Request,
+        api_version: Option<&str>,
+        handler: &mut H,
+        hold: &[u32],
+    ) -> Result<Attempt, (DockerError, bool)> {
+        let http = self.http_request(request, api_version);
+        if http.is_cancelled() {
+            return Err((http.cancelled(), false));
+        }
+
+        let delivered = Cell::new(false);
+        let mut delivery = self.delivery(request, handler, hold, &delivered);
+        match self.transport.send(&http, &mut delivery) {
+            Ok(status) => {
+                delivery.response.status = status;
+                Ok(self.finish(delivery))
+            }
+            Err(error) => Err((error, delivered.get())),
+        }
+    }
+
+    /// The version `request` is prefixed with, which is negotiated with the
+    /// daemon by the first request which needs one.
+    fn api_version_for(&self, request: &Request) -> DockerResult<Option<String>> {
+        if request.unversioned {
+            return Ok(None);
+        }
+        match self.api_version() {
+            Some(api_version) => Ok(Some(api_version)),
+            None => self.negotiate_api_version().map(Some),
+        }
+    }
+
+    /// How `request` goes over the wire to this client's daemon.
+    fn http_request<'a>(
+        &'a self,
+        request: &'a Request,
+        api_version: Option<&'a str>,
+    ) -> HttpRequest<'a> {
+        HttpRequest {
+            method: request.method.into(),
+            host: &self.docker_host,
+            unix_socket: self.unix_socket.as_deref(),
+            tls: self.tls.as_ref(),
+            api_version,
+            path: &request.path,
+            content_type: request.content_type,
+            body: request.body.as_deref().unwrap_or_default(),
+            connect_timeout: self.connect_timeout,
+            timeout: match request.timeout {
+                Some(timeout) => Some(timeout),
+                None if request.long_running => self.long_running_timeout,
+                None => self.timeout,
+            },
+            cancellation: self.cancellation.as_ref(),
+        }
+    }
+
+    fn delivery<'a, H: ResponseHandler>(
+        &self,
+        request: &Request,
+        handler: &'a mut H,
+        hold: &'a [u32],
+        delivered: &'a Cell<bool>,
+    ) -> Delivery<'a, H> {
+        Delivery {
+            handler,
+            hold,
+            delivered,
+            response: Response::new(0),
+            recording: self.recorder.as_ref().map(|_| Exchange {
+                method: request.method.to_string(),
+                path: request.path.clone(),
+                body: request
+                    .body
+                    .as_deref()
+                    .filter(|body| !body.is_empty())
+                    .map(Chunk::from),
+                status: 0,
+                headers: vec![],
+                chunks: vec![],
+            }),
+        }
+    }
+
+    fn finish<H: ResponseHandler>(&self, delivery: Delivery<'_, H>) -> Attempt {
+        let Delivery {
+            response,
+            recording,
+            hold,
+            ..
+        } = delivery;
+        if let (Some(recorder), Some(mut exchange)) = (&self.recorder, recording) {
+            exchange.status = response.status;
+            recorder.record(exchange);
+        }
+
+        Attempt {
+            held: hold.contains(&response.status),
+            response,
+        }
+    }
+}
+
+/// The outcome of a single attempt at a request.
+struct Attempt {
+    response: Response,
+    /// Whether the body was held back from the handler for a retry.
+    held: bool,
+}
+
+/// Passes a response on to the handler of the request it answers, keeping
+/// track of its status and the start of an error body along the way.
+struct Delivery<'a, H> {
+    handler: &'a mut H,
+    /// Codes whose body is kept back from `handler` for a retry.
+    hold: &'a [u32],
+    /// Set once any of the body has reached `handler`.
+    delivered: &'a Cell<bool>,
+    response: Response,
+    /// The exchange being recorded, when the client has a recorder.
+    recording: Option<Exchange>,
+}
+impl<H: ResponseHandler> ResponseSink for Delivery<'_, H> {
+    fn header(&mut self, data: &[u8]) -> bool {
+        if let Some(code) = status_code(data) {
+            // Interim responses such as `100 Continue` are superseded.
+            self.response = Response::new(code);
+            if let Some(recording) = &mut self.recording {
+                recording.headers.clear();
+                recording.chunks.clear();
+            }
+        }
+        if let Some(recording) = &mut self.recording {
+            let line = String::from_utf8_lossy(data);
+            recording
+                .headers
+                .push(line.trim_end_matches(&['\r', '\n'][..]).to_string());
+        }
+        self.handler.header(data).is_ok()
+    }
+
+    fn write(&mut self, data: &[u8]) -> bool {
+        self.response.capture(data);
+        if let Some(recording) = &mut self.recording {
+            recording.chunks.push(data.into());
+        }
+        if self.hold.contains(&self.response.status) {
+            true
+        } else {
+            self.delivered.set(true);
+            self.handler.write(data).is_ok()
+        }
+    }
+}
+
+/// The transport a client starts with.
+#[cfg(feature = "curl")]
+fn default_transport() -> Arc<dyn Transport> {
+    Arc::new(CurlTransport::new())
+}
+
+/// The transport a client starts with.
+#[cfg(all(unix, not(feature = "curl")))]
+fn default_transport() -> Arc<dyn Transport> {
+    Arc::new(UnixTransport::new())
+}
+
+/// Picks the response code out of an HTTP status line such as
+/// `HTTP/1.1 200 OK`; other header lines give `None`.
+fn status_code(header: &[u8]) -> Option<u32> {
+    let line = std::str::from_utf8(header).ok()?;
+    if !line.starts_with("HTTP/") {
+        return None;
+    }
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Resolves the Unix socket the daemon is listening on.
+///
+/// Honours `DOCKER_HOST` when it holds a `unix://` URL, which is how rootless
+/// Docker and Podman advertise their sockets, and otherwise falls back to
+/// `DEFAULT_UNIX_SOCKET`.
+pub fn resolve_unix_socket() -> PathBuf {
+    match env::var("DOCKER_HOST").map(|host| DockerHost::parse(&host)) {
+        Ok(Ok(DockerHost::Unix(path))) => path,
+        _ => PathBuf::from(DEFAULT_UNIX_SOCKET),
+    }
+}
+
+/// The directory the Docker CLI keeps its configuration in: `DOCKER_CONFIG`,
+/// or `~/.docker`.
+fn docker_config_dir(var: &dyn Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    var("DOCKER_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".docker")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::request::Request;
+    #[cfg(unix)]
+    use crate::client::retry::RetryPolicy;
+    use crate::client::tls::TlsConfig;
+    use crate::client::{DockerClient, DEFAULT_UNIX_SOCKET};
+    use crate::error::{DockerError, Operation};
+    #[cfg(unix)]
+    use crate::output::Discard;
+    #[cfg(unix)]
+    use crate::testing::{MockDaemon, MockResponse, SilentDaemon};
+    use std::collections::HashMap;
+    use std::path::Path;
+    #[cfg(unix)]
+    use std::time::Duration;
+
+    fn client_from(vars: &[(&str, &str)]) -> DockerClient {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        DockerClient::from_vars(|name| vars.get(name).cloned()).unwrap()
+    }
+
+    #[test]
+    fn with_unix_socket_overrides_default() {
+        let client = DockerClient::new("localhost", false).with_unix_socket("/tmp/docker.sock");
+        assert!(client.use_unix_socket());
+        assert_eq!(client.unix_socket(), Some(Path::new("/tmp/docker.sock")));
+    }
+
+    #[test]
+    fn from_env_defaults_to_the_unix_socket() {
+        let client = client_from(&[]);
+        assert_eq!(client.unix_socket(), Some(Path::new(DEFAULT_UNIX_SOCKET)));
+        let ping = Request::get(Operation::SystemPing, "/_ping");
+        assert_eq!(
+            client.http_request(&ping, None).url(),
+            "http://localhost/_ping"
+        );
+    }
+
+    #[test]
+    fn from_env_reads_tcp_tls_and_api_version() {
+        let certs = std::env::temp_dir().join(format!("dockurl-certs-{}", std::process::id()));
+        std::fs::create_dir_all(&certs).unwrap();
+        for file in &["cert.pem", "key.pem"] {
+            std::fs::write(certs.join(file), "").unwrap();
+        }
+
+        let client = client_from(&[
+            ("DOCKER_HOST", "tcp://10.0.0.5:2376"),
+            ("DOCKER_TLS_VERIFY", "1"),
+            ("DOCKER_CERT_PATH", certs.to_str().unwrap()),
+            ("DOCKER_API_VERSION", "1.40"),
+        ]);
+        assert!(!client.use_unix_socket());
+        assert_eq!(
+            client.tls(),
+            Some(&TlsConfig {
+                ca_cert: None,
+                client_cert: Some(certs.join("cert.pem")),
+                client_key: Some(certs.join("key.pem")),
+                verify: true,
+            })
+        );
+        assert_eq!(client.api_version().as_deref(), Some("1.40"));
+        let list = Request::get(Operation::ContainerInspect, "/containers/json");
+        assert_eq!(
+            client.http_request(&list, Some("1.40")).url(),
+            "https://10.0.0.5:2376/v1.40/containers/json"
+        );
+        std::fs::remove_dir_all(certs).unwrap();
+
+        let vars = |name: &str| match name {
+            "DOCKER_HOST" => Some("tcp://10.0.0.5:2376".to_string()),
+            "DOCKER_TLS_VERIFY" => Some("1".to_string()),
+            "DOCKER_CERT_PATH" => Some("/nonexistent/certs".to_string()),
+            _ => None,
+        };
+        match DockerClient::from_vars(vars) {
+            Err(DockerError::InvalidTlsConfigError(message)) => {
+                assert_eq!(message, "/nonexistent/certs/cert.pem does not exist")
+            }
+            other => panic!("expected missing certificates, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_env_needs_tls_verify_for_tls() {
+        let client = client_from(&[
+            ("DOCKER_HOST", "tcp://10.0.0.5:2376"),
+            ("DOCKER_CERT_PATH", "/nonexistent/certs"),
+        ]);
+        assert!(client.tls().is_none());
+        let ping = Request::get(Operation::SystemPing, "/_ping");
+        assert_eq!(
+            client.http_request(&ping, None).url(),
+            "http://10.0.0.5:2376/_ping"
+        );
+    }
+
+    #[test]
+    fn from_env_ignores_tls_for_unix_sockets() {
+        let client = client_from(&[
+            ("DOCKER_HOST", "unix:///tmp/docker.sock"),
+            ("DOCKER_TLS_VERIFY", "1"),
+        ]);
+        assert!(client.tls().is_none());
+        assert_eq!(client.unix_socket(), Some(Path::new("/tmp/docker.sock")));
+    }
+
+    #[test]
+    fn from_env_uses_the_selected_context() {
+        let dir = std::env::temp_dir().join(format!("dockurl-client-{}", std::process::id()));
+        let meta_dir = dir.join("contexts/meta/0a1b");
+        std::fs::create_dir_all(&meta_dir).unwrap();
+        std::fs::write(
+            meta_dir.join("meta.json"),
+            r#"{"Name":"podman","Endpoints":{"docker":{"Host":"unix:///run/podman/podman.sock"}}}"#,
+        )
+        .unwrap();
+        let config_dir = dir.to_str().unwrap();
+
+        let client = client_from(&[("DOCKER_CONFIG", config_dir), ("DOCKER_CONTEXT", "podman")]);
+        assert_eq!(
+            client.unix_socket(),
+            Some(Path::new("/run/podman/podman.sock"))
+        );
+        let client = client_from(&[
+            ("DOCKER_CONFIG", config_dir),
+            ("DOCKER_CONTEXT", "podman"),
+            ("DOCKER_HOST", "tcp://10.0.0.5:2375"),
+        ]);
+        assert!(!client.use_unix_socket());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn timeouts_surface_as_timeout_errors() {
+        let daemon = SilentDaemon::start().unwrap();
+        let client = daemon.client().with_timeout(Duration::from_millis(200));
+        match client.ping() {
+            Err(DockerError::Timeout(request)) => assert_eq!(request, "GET /_ping"),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn retries_transient_response_codes() {
+        let daemon = MockDaemon::start().unwrap();
+        let busy = MockResponse::json(503, r#"{"message":"daemon busy"}"#);
+        daemon
+            .route_once("GET", "/_ping", busy.clone())
+            .route_once("GET", "/_ping", busy.clone())
+            .route_once(
+                "GET",
+                "/_ping",
+                MockResponse::new(200)
+                    .header("API-Version", "1.41")
+                    .body(b"OK"),
+            )
+            .route("GET", "/_ping", busy);
+
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..RetryPolicy::new(3)
+        };
+        let client = daemon.client().with_retry_policy(policy);
+        assert_eq!(client.ping().unwrap().api_version.as_deref(), Some("1.41"));
+
+        let client = client.with_retry_policy(RetryPolicy::new(2));
+        match client.ping() {
+            Err(DockerError::RetriesExhaustedError(attempts, error)) => {
+                assert_eq!(attempts, 2);
+                assert!(error.to_string().contains("daemon busy"));
+            }
+            other => panic!("expected retries to be exhausted, got {:?}", other),
+        }
+
+        assert_eq!(daemon.requests().len(), 5);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn error_responses_become_api_errors() {
+        let daemon = MockDaemon::start().unwrap();
+        daemon.route(
+            "POST",
+            "/containers/*/stop",
+            MockResponse::json(404, "{\"message\":\"No such container: abc\"}\n"),
+        );
+
+        let error = daemon.client().stop_container("abc", Discard).unwrap_err();
+        assert!(error.is_not_found());
+        let api_error = error.api_error().unwrap();
+        assert_eq!(api_error.operation, Operation::ContainerStop);
+        assert_eq!(api_error.resource.as_deref(), Some("abc"));
+        assert_eq!(api_error.message, "No such container: abc");
+    }
+
+    #[test]
+    fn from_env_rejects_named_pipes() {
+        let vars = |name: &str| match name {
+            "DOCKER_HOST" => Some("npipe:////./pipe/docker_engine".to_string()),
+            _ => None,
+        };
+        assert!(DockerClient::from_vars(vars).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::client::transport::{HttpRequest, ResponseSink, Transport};
+use crate::error::DockerError::NoRecordedResponseError;
+use crate::error::DockerResult;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// A request and the response the daemon gave to it, as recorded by a
+/// `Recorder`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Exchange {
+    pub method: String,
+    /// Path and query string, without the API version prefix, so that a
+    /// fixture replays whichever version the client negotiates.
+    pub path: String,
+    /// The request body, when it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Chunk>,
+    pub status: u32,
+    /// Header lines without their line endings, starting with the status
+    /// line and ending with the blank line which closes the headers.
+    pub headers: Vec<String>,
+    /// The body in the chunks it arrived in.
+    pub chunks: Vec<Chunk>,
+}
+
+/// A piece of a response body; text when it is valid UTF-8, which keeps
+/// fixtures of JSON responses readable.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Chunk {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+impl Chunk {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Chunk::Text(text) => text.as_bytes(),
+            Chunk::Bytes(bytes) => bytes,
+        }
+    }
+}
+impl From<&[u8]> for Chunk {
+    fn from(data: &[u8]) -> Self {
+        match String::from_utf8(data.to_vec()) {
+            Ok(text) => Chunk::Text(text),
+            Err(error) => Chunk::Bytes(error.into_bytes()),
+        }
+    }
+}
+
+/// The exchanges of a recorded session, stored as JSON.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fixture {
+    pub exchanges: Vec<Exchange>,
+}
+impl Fixture {
+    pub fn load<P: AsRef<Path>>(path: P) -> DockerResult<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> DockerResult<()> {
+        Ok(fs::write(path, serde_json::to_vec_pretty(self)?)?)
+    }
+}
+
+/// Collects every exchange a client has with the daemon. Clones share the
+/// recording, so one can be kept to save it once the client is done.
+///
+/// Each attempt is recorded separately, so retried calls replay the same
+/// way. Calls which fail before the daemon answers are not recorded.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    fixture: Arc<Mutex<Fixture>>,
+}
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The exchanges recorded so far.
+    pub fn fixture(&self) -> Fixture {
+        self.fixture.lock().map(|f| f.clone()).unwrap_or_default()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> DockerResult<()> {
+        self.fixture().save(path)
+    }
+
+    pub(crate) fn record(&self, exchange: Exchange) {
+        if let Ok(mut fixture) = self.fixture.lock() {
+            fixture.exchanges.push(exchange);
+        }
+    }
+}
+
+/// Serves the exchanges of a fixture in place of the daemon. Each request
+/// is answered by the first unused exchange with the same method, path and
+/// body.
+#[derive(Debug, Clone)]
+pub(crate) struct Replay {
+    exchanges: Arc<Mutex<Vec<Option<Exchange>>>>,
+}
+impl Replay {
+    pub fn new(fixture: Fixture) -> Self {
+        Self {
+            exchanges: Arc::new(Mutex::new(
+                fixture.exchanges.into_iter().map(Some).collect(),
+            )),
+        }
+    }
+
+    fn take(&self, request: &HttpRequest<'_>) -> DockerResult<Exchange> {
+        let exchange = self.exchanges.lock().ok().and_then(|mut exchanges| {
+            exchanges
+                .iter_mut()
+                .find(|exchange| {
+                    exchange.as_ref().is_some_and(|exchange| {
+                        exchange.method == request.method
+                            && exchange.path == request.path
+                            && exchange.body.as_ref().map_or(&[][..], Chunk::as_bytes)
+                                == request.body
+                    })
+                })
+                .and_then(Option::take)
+        });
+
+        exchange
+            .ok_or_else(|| NoRecordedResponseError(format!("{} {}", request.method, request.path)))
+    }
+}
+impl Transport for Replay {
+    fn send(
+        &self,
+        request: &HttpRequest<'_>,
+        response: &mut dyn ResponseSink,
+    ) -> DockerResult<u32> {
+        let exchange = self.take(request)?;
+        for header in &exchange.headers {
+            if !response.header(format!("{}\r\n", header).as_bytes()) {
+                return Err(request.aborted());
+            }
+        }
+        for chunk in &exchange.chunks {
+            if !response.write(chunk.as_bytes()) {
+                return Err(request.aborted());
+            }
+        }
+        Ok(exchange.status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    use crate::client::replay::Fixture;
+    use crate::client::replay::{Chunk, Recorder};
+    use crate::client::DockerClient;
+    use crate::error::DockerError;
+    use crate::network::NetworkMode;
+    #[cfg(unix)]
+    use crate::output::Collect;
+    use crate::output::Discard;
+    #[cfg(unix)]
+    use crate::testing::MockDaemon;
+    use crate::testing::{MockResponse, MockTransport};
+
+    #[test]
+    fn chunks_keep_binary_data() {
+        assert_eq!(Chunk::from(&b"{}"[..]), Chunk::Text("{}".to_string()));
+        assert_eq!(
+            Chunk::from(&[1, 0, 0, 0, 0, 0, 0, 0x80][..]),
+            Chunk::Bytes(vec![1, 0, 0, 0, 0, 0, 0, 0x80])
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn replays_a_recorded_session_without_a_daemon() {
+        let daemon = MockDaemon::start().unwrap();
+        daemon
+            .route(
+                "GET",
+                "/_ping",
+                MockResponse::new(200)
+                    .header("API-Version", "1.40")
+                    .body(b"OK"),
+            )
+            .route(
+                "GET",
+                "/containers/*/json",
+                MockResponse::json(200, r#"{"Config":{"Tty":false}}"#),
+            )
+            .route(
+                "GET",
+                "/containers/*/logs",
+                MockResponse::stream(
+                    200,
+                    &[
+                        &b"\x01\0\0\0\0\0\0\x06hello\n"[..],
+                        b"\x02\0\0\0\0\0\0\x01\xff",
+                    ],
+                ),
+            )
+            .route(
+                "POST",
+                "/containers/*/start",
+                MockResponse::json(404, r#"{"message":"No such container: abc"}"#),
+            );
+
+        let recorder = Recorder::new();
+        let client = daemon.client().with_recorder(recorder.clone());
+        client.negotiate_api_version().unwrap();
+        let logs = client.get_container_logs("abc", Collect::new()).unwrap();
+        assert!(client.start_container("abc", Collect::new()).is_err());
+
+        let path =
+            std::env::temp_dir().join(format!("dockurl-fixture-{}.json", std::process::id()));
+        recorder.save(&path).unwrap();
+        let fixture = Fixture::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(fixture, recorder.fixture());
+        assert_eq!(fixture.exchanges.len(), 4);
+        drop(daemon);
+
+        let replayed = DockerClient::new("localhost", false)
+            .with_unix_socket("/nonexistent/docker.sock")
+            .with_replay(fixture);
+        assert_eq!(replayed.negotiate_api_version().unwrap(), "1.40");
+        assert_eq!(
+            replayed.get_container_logs("abc", Collect::new()).unwrap(),
+            logs
+        );
+        let error = replayed.start_container("abc", Collect::new()).unwrap_err();
+        assert!(error.is_not_found());
+        assert_eq!(error.api_error().unwrap().message, "No such container: abc");
+
+        match replayed.start_container("abc", Collect::new()) {
+            Err(DockerError::NoRecordedResponseError(request)) => {
+                assert_eq!(request, "POST /containers/abc/start")
+            }
+            other => panic!("expected no recorded response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn replays_by_request_body() {
+        let transport = MockTransport::new();
+        transport
+            .route_once(
+                "POST",
+                "/networks/create",
+                MockResponse::json(201, r#"{"Id":"f0f0f0f0f0f0f0f0"}"#),
+            )
+            .route_once(
+                "POST",
+                "/networks/create",
+                MockResponse::json(201, r#"{"Id":"b0b0b0b0b0b0b0b0"}"#),
+            );
+        let recorder = Recorder::new();
+        let client = transport.client().with_recorder(recorder.clone());
+        client
+            .create_network("front", NetworkMode::Bridge, Discard)
+            .unwrap();
+        client
+            .create_network("back", NetworkMode::Bridge, Discard)
+            .unwrap();
+
+        let replayed = DockerClient::new("localhost", false).with_replay(recorder.fixture());
+        assert_eq!(
+            replayed
+                .create_network("back", NetworkMode::Bridge, Discard)
+                .unwrap(),
+            "b0b0b0b0b0b0"
+        );
+        assert_eq!(
+            replayed
+                .create_network("front", NetworkMode::Bridge, Discard)
+                .unwrap(),
+            "f0f0f0f0f0f0"
+        );
+        match replayed.create_network("front", NetworkMode::Bridge, Discard) {
+            Err(DockerError::NoRecordedResponseError(request)) => {
+                assert_eq!(request, "POST /networks/create")
+            }
+            other => panic!("expected no recorded response, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::error::{ApiError, DockerError, Operation};
+use std::time::Duration;
+use strum_macros::{Display, IntoStaticStr};
+
+/// How much of an error response is kept for its message.
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// The HTTP methods used by the Engine API.
+#[derive(Display, IntoStaticStr, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "UPPERCASE")]
+pub(crate) enum Method {
+    Get,
+    Post,
+    Delete,
+}
+
+/// A single call to the daemon, independent of the handle used to send it.
+#[derive(Debug, Clone)]
+pub(crate) struct Request {
+    pub operation: Operation,
+    /// The id or name of what the request acts on, for error reporting.
+    pub resource: Option<String>,
+    pub method: Method,
+    /// Path and query string, without the API version prefix.
+    pub path: String,
+    pub content_type: Option<&'static str>,
+    pub body: Option<Vec<u8>>,
+    /// Whether the path is sent without the API version prefix, as
+    /// `/_ping` must be while negotiating.
+    pub unversioned: bool,
+    /// Whether the call streams for as long as the daemon keeps working
+    /// (builds, pulls, attach, logs, wait), which puts it under the client's
+    /// long-running timeout rather than its regular one.
+    pub long_running: bool,
+    /// Whether sending the request twice has the same effect as sending it
+    /// once, which makes it safe to retry.
+    pub idempotent: bool,
+    /// A limit on this request in place of the client's timeouts.
+    pub timeout: Option<Duration>,
+}
+impl Request {
+    fn new(operation: Operation, method: Method, path: String) -> Self {
+        Self {
+            operation,
+            resource: None,
+            method,
+            path,
+            content_type: None,
+            body: None,
+            unversioned: false,
+            long_running: false,
+            idempotent: method != Method::Post,
+            timeout: None,
+        }
+    }
+
+    pub fn get<S: Into<String>>(operation: Operation, path: S) -> Self {
+        Self::new(operation, Method::Get, path.into())
+    }
+
+    pub fn post<S: Into<String>>(operation: Operation, path: S) -> Self {
+        Self::new(operation, Method::Post, path.into())
+    }
+
+    pub fn delete<S: Into<String>>(operation: Operation, path: S) -> Self {
+        Self::new(operation, Method::Delete, path.into())
+    }
+
+    pub fn resource(mut self, resource: &str) -> Self {
+        self.resource = Some(resource.to_string());
+        self
+    }
+
+    /// Sends `json` as an `application/json` body.
+    pub fn json(self, json: String) -> Self {
+        self.body("application/json", json.into_bytes())
+    }
+
+    pub fn body(mut self, content_type: &'static str, body: Vec<u8>) -> Self {
+        self.content_type = Some(content_type);
+        self.body = Some(body);
+        self
+    }
+
+    pub fn unversioned(mut self) -> Self {
+        self.unversioned = true;
+        self
+    }
+
+    pub fn long_running(mut self) -> Self {
+        self.long_running = true;
+        self
+    }
+
+    /// Marks a `POST` which the daemon handles idempotently.
+    pub fn idempotent(mut self) -> Self {
+        self.idempotent = true;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The error for an unexpected `response` to this request.
+    pub fn error(&self, response: &Response) -> DockerError {
+        ApiError::from_body(
+            self.operation,
+            self.resource.as_deref(),
+            response.status,
+            &response.error_body,
+        )
+        .into()
+    }
+
+    /// The error for a failure the daemon reported with `message`, such as an
+    /// error line in a build stream.
+    pub fn error_message(&self, status: u32, message: impl Into<String>) -> DockerError {
+        ApiError::new(self.operation, self.resource.as_deref(), status, message).into()
+    }
+}
+
+/// The parts of a response dockurl looks at after the body has been handed
+/// to the request's handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub status: u32,
+    /// The start of the body of an error response (codes of 400 and above),
+    /// which holds the daemon's message.
+    pub error_body: Vec<u8>,
+}
+impl Response {
+    pub fn new(status: u32) -> Self {
+        Self {
+            status,
+            error_body: Vec::new(),
+        }
+    }
+
+    /// Keeps `data` if this is an error response with room left for it.
+    pub fn capture(&mut self, data: &[u8]) {
+        if self.status >= 400 && self.error_body.len() < MAX_ERROR_BODY {
+            let room = MAX_ERROR_BODY - self.error_body.len();
+            self.error_body
+                .extend_from_slice(&data[..data.len().min(room)]);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::client::request::Request;
+use crate::error::DockerError;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// When and how often the client retries a failed call.
+///
+/// Only calls which are safe to repeat are retried: `GET` and `DELETE`
+/// requests, and `POST`s such as starting, stopping or waiting on a container
+/// which the daemon treats idempotently. Set `retry_non_idempotent` to retry
+/// everything else as well.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts made in total, including the first.
+    pub max_attempts: u32,
+    /// Delay before the second attempt; doubled for every attempt after it.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between attempts.
+    pub max_backoff: Duration,
+    /// Response codes which are treated as transient, such as a daemon
+    /// reporting that it is busy.
+    pub retry_on_status: Vec<u32>,
+    /// Whether refused, reset or empty connections are retried. Failures are
+    /// only retried when no part of the response has been handed on yet.
+    pub retry_on_connection_errors: bool,
+    /// Whether calls which hit the client's timeout are retried.
+    pub retry_on_timeouts: bool,
+    /// Whether calls which are not idempotent are retried.
+    pub retry_non_idempotent: bool,
+}
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            retry_on_status: vec![500, 502, 503, 504],
+            retry_on_connection_errors: true,
+            retry_on_timeouts: false,
+            retry_non_idempotent: false,
+        }
+    }
+}
+impl RetryPolicy {
+    /// The default policy, making at most `max_attempts` attempts.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn applies_to(&self, request: &Request) -> bool {
+        self.max_attempts > 1 && (request.idempotent || self.retry_non_idempotent)
+    }
+
+    pub(crate) fn retries_error(&self, error: &DockerError) -> bool {
+        match error {
+            #[cfg(feature = "curl")]
+            DockerError::CurlError(e) => {
+                self.retry_on_connection_errors
+                    && (e.is_couldnt_connect()
+                        || e.is_send_error()
+                        || e.is_recv_error()
+                        || e.is_got_nothing()
+                        || e.is_partial_file())
+            }
+            // The same failures as seen by transports other than curl.
+            DockerError::IoError(e) => {
+                self.retry_on_connection_errors
+                    && matches!(
+                        e.kind(),
+                        ErrorKind::NotFound
+                            | ErrorKind::ConnectionRefused
+                            | ErrorKind::ConnectionReset
+                            | ErrorKind::ConnectionAborted
+                            | ErrorKind::BrokenPipe
+                            | ErrorKind::UnexpectedEof
+                    )
+            }
+            DockerError::Timeout(_) => self.retry_on_timeouts,
+            _ => false,
+        }
+    }
+
+    /// The delay before making attempt number `attempt + 1`.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::request::Request;
+    use crate::client::retry::RetryPolicy;
+    use crate::error::{DockerError, Operation};
+    use std::io;
+    use std::time::Duration;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(64), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn only_idempotent_requests_are_retried_by_default() {
+        let policy = RetryPolicy::default();
+        assert!(policy.applies_to(&Request::get(
+            Operation::ContainerInspect,
+            "/containers/abc/json"
+        )));
+        assert!(policy.applies_to(
+            &Request::post(Operation::ContainerStop, "/containers/abc/stop").idempotent()
+        ));
+        assert!(!policy.applies_to(&Request::post(
+            Operation::ContainerCreate,
+            "/containers/create"
+        )));
+        assert!(!RetryPolicy::new(1).applies_to(&Request::get(
+            Operation::ContainerInspect,
+            "/containers/abc/json"
+        )));
+    }
+
+    #[test]
+    fn connection_failures_of_any_transport_are_retried() {
+        let policy = RetryPolicy::default();
+        let refused = io::Error::from(io::ErrorKind::ConnectionRefused);
+        assert!(policy.retries_error(&DockerError::IoError(refused)));
+        let invalid = io::Error::from(io::ErrorKind::InvalidData);
+        assert!(!policy.retries_error(&DockerError::IoError(invalid)));
+        assert!(!policy.retries_error(&DockerError::Timeout("GET /_ping".to_string())));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::error::DockerError::InvalidTlsConfigError;
+use crate::error::DockerResult;
+#[cfg(feature = "curl")]
+use curl::easy::Easy;
+use std::path::{Path, PathBuf};
+
+/// TLS material for talking to a daemon started with `--tlsverify`.
+///
+/// Files are expected to be PEM encoded, matching what `docker-machine` and
+/// `dockerd --tlsverify` setups generate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// CA bundle used to verify the daemon's certificate.
+    pub ca_cert: Option<PathBuf>,
+    /// Client certificate presented to the daemon.
+    pub client_cert: Option<PathBuf>,
+    /// Private key for `client_cert`.
+    pub client_key: Option<PathBuf>,
+    /// Whether the daemon's certificate and host name are verified.
+    pub verify: bool,
+}
+impl TlsConfig {
+    /// Uses the `ca.pem`, `cert.pem` and `key.pem` layout the Docker CLI
+    /// expects in `DOCKER_CERT_PATH`. Without `ca.pem` the daemon is verified
+    /// against the system's CAs, but `cert.pem` and `key.pem` must both be
+    /// there; a missing one is an `InvalidTlsConfigError` naming it.
+    pub fn from_cert_path<P: AsRef<Path>>(cert_path: P, verify: bool) -> DockerResult<Self> {
+        let cert_path = cert_path.as_ref();
+        let required = |name: &str| {
+            let path = cert_path.join(name);
+            if path.is_file() {
+                Ok(path)
+            } else {
+                Err(InvalidTlsConfigError(format!(
+                    "{} does not exist",
+                    path.display()
+                )))
+            }
+        };
+
+        Ok(Self {
+            ca_cert: Some(cert_path.join("ca.pem")).filter(|path| path.is_file()),
+            client_cert: Some(required("cert.pem")?),
+            client_key: Some(required("key.pem")?),
+            verify,
+        })
+    }
+
+    /// A config which verifies the daemon against `ca_cert` and authenticates
+    /// with `client_cert` and `client_key`.
+    pub fn new<P: AsRef<Path>>(ca_cert: P, client_cert: P, client_key: P) -> Self {
+        Self {
+            ca_cert: Some(ca_cert.as_ref().to_path_buf()),
+            client_cert: Some(client_cert.as_ref().to_path_buf()),
+            client_key: Some(client_key.as_ref().to_path_buf()),
+            verify: true,
+        }
+    }
+
+    /// Applies this config to `easy`.
+    #[cfg(feature = "curl")]
+    pub(crate) fn configure(&self, easy: &mut Easy) -> DockerResult<()> {
+        if self.client_cert.is_some() != self.client_key.is_some() {
+            return Err(InvalidTlsConfigError(
+                "a client certificate and key must be given together".to_string(),
+            ));
+        }
+
+        if let Some(ca_cert) = &self.ca_cert {
+            easy.cainfo(ca_cert)?;
+        }
+        if let (Some(client_cert), Some(client_key)) = (&self.client_cert, &self.client_key) {
+            easy.ssl_cert(client_cert)?;
+            easy.ssl_cert_type("PEM")?;
+            easy.ssl_key(client_key)?;
+            easy.ssl_key_type("PEM")?;
+        }
+        easy.ssl_verify_peer(self.verify)?;
+        easy.ssl_verify_host(self.verify)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::tls::TlsConfig;
+    #[cfg(feature = "curl")]
+    use curl::easy::Easy;
+    #[cfg(feature = "curl")]
+    use std::path::PathBuf;
+
+    #[test]
+    fn from_cert_path_requires_cert_and_key() {
+        let dir = std::env::temp_dir().join(format!("dockurl-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let error = TlsConfig::from_cert_path(&dir, true).unwrap_err();
+        assert!(error.to_string().contains("cert.pem does not exist"));
+        std::fs::write(dir.join("cert.pem"), "").unwrap();
+        let error = TlsConfig::from_cert_path(&dir, true).unwrap_err();
+        assert!(error.to_string().contains("key.pem does not exist"));
+
+        std::fs::write(dir.join("key.pem"), "").unwrap();
+        assert_eq!(
+            TlsConfig::from_cert_path(&dir, true).unwrap(),
+            TlsConfig {
+                ca_cert: None,
+                client_cert: Some(dir.join("cert.pem")),
+                client_key: Some(dir.join("key.pem")),
+                verify: true,
+            }
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "curl")]
+    fn configure_requires_cert_and_key_together() {
+        let tls = TlsConfig {
+            client_cert: Some(PathBuf::from("cert.pem")),
+            ..TlsConfig::default()
+        };
+        assert!(tls.configure(&mut Easy::new()).is_err());
+    }
+}
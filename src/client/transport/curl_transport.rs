@@ -0,0 +1,127 @@
+use crate::client::transport::{HttpRequest, ResponseSink, Transport};
+use crate::error::DockerError::CurlError;
+use crate::error::DockerResult;
+use curl::easy::{Easy, List};
+use std::cell::{Cell, RefCell};
+use std::sync::Mutex;
+
+/// How many idle curl handles a transport keeps for reuse.
+const MAX_IDLE_HANDLES: usize = 8;
+
+/// Sends requests with libcurl, over TCP, TLS or a Unix socket.
+///
+/// Curl handles are pooled and reused between calls so that keep-alive
+/// connections to the daemon survive from one request to the next.
+#[derive(Debug, Default)]
+pub struct CurlTransport {
+    handles: Mutex<Vec<Easy>>,
+}
+impl CurlTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn perform(
+        &self,
+        easy: &mut Easy,
+        request: &HttpRequest<'_>,
+        response: &mut dyn ResponseSink,
+    ) -> DockerResult<u32> {
+        if let Some(unix_socket) = request.unix_socket {
+            easy.unix_socket_path(Some(unix_socket))?;
+        }
+        if let Some(tls) = request.tls {
+            tls.configure(easy)?;
+        }
+        if let Some(connect_timeout) = request.connect_timeout {
+            easy.connect_timeout(connect_timeout)?;
+        }
+        if let Some(timeout) = request.timeout {
+            easy.timeout(timeout)?;
+        }
+        if request.cancellation.is_some() {
+            // Curl checks in through the progress callback about once a
+            // second, even while the daemon is silent.
+            easy.progress(true)?;
+        }
+
+        easy.url(&request.url())?;
+        match request.method {
+            "GET" => easy.get(true)?,
+            "POST" => {
+                easy.post(true)?;
+                easy.post_field_size(request.body.len() as u64)?;
+                easy.post_fields_copy(request.body)?;
+            }
+            method => easy.custom_request(method)?,
+        }
+        if let Some(content_type) = request.content_type {
+            let mut headers = List::new();
+            headers.append(&format!("Content-Type: {}", content_type))?;
+            easy.http_headers(headers)?;
+        }
+
+        let response = RefCell::new(response);
+        let aborted = Cell::new(false);
+        {
+            let mut transfer = easy.transfer();
+            transfer.header_function(|data| {
+                let proceed = response.borrow_mut().header(data);
+                aborted.set(!proceed);
+                proceed
+            })?;
+            if let Some(token) = request.cancellation {
+                transfer.progress_function(move |_, _, _, _| !token.is_cancelled())?;
+            }
+            transfer.write_function(|data| {
+                if response.borrow_mut().write(data) {
+                    Ok(data.len())
+                } else {
+                    // Taking less than was offered makes curl give up.
+                    aborted.set(true);
+                    Ok(0)
+                }
+            })?;
+            transfer.perform().map_err(|e| {
+                if request.is_cancelled() {
+                    request.cancelled()
+                } else if aborted.get() {
+                    request.aborted()
+                } else if e.is_operation_timedout() {
+                    request.timed_out()
+                } else {
+                    CurlError(e)
+                }
+            })?;
+        }
+
+        Ok(easy.response_code()?)
+    }
+}
+impl Transport for CurlTransport {
+    /// A pooled handle is used when one is idle; handles are reset rather than
+    /// dropped afterwards so their connections stay open for the next call.
+    fn send(
+        &self,
+        request: &HttpRequest<'_>,
+        response: &mut dyn ResponseSink,
+    ) -> DockerResult<u32> {
+        let pooled = self
+            .handles
+            .lock()
+            .ok()
+            .and_then(|mut handles| handles.pop());
+        let mut easy = pooled.unwrap_or_else(Easy::new);
+        easy.reset();
+
+        let result = self.perform(&mut easy, request, response);
+
+        if let Ok(mut handles) = self.handles.lock() {
+            if handles.len() < MAX_IDLE_HANDLES {
+                handles.push(easy);
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(feature = "curl")]
+mod curl_transport;
+#[cfg(unix)]
+mod unix_transport;
+
+#[cfg(feature = "curl")]
+pub use crate::client::transport::curl_transport::CurlTransport;
+#[cfg(unix)]
+pub use crate::client::transport::unix_transport::UnixTransport;
+
+use crate::client::cancellation::CancellationToken;
+use crate::client::tls::TlsConfig;
+use crate::error::DockerError::{Cancelled, IoError, Timeout};
+use crate::error::{DockerError, DockerResult};
+use std::fmt::Debug;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// Carries requests to the daemon and streams its responses back.
+///
+/// `DockerClient` handles everything above HTTP (API versions, retries,
+/// error mapping) and leaves the wire to a transport: `CurlTransport` with the
+/// default `curl` feature, `UnixTransport` for Unix builds without it, or
+/// an in-memory implementation in tests. Transports are shared between clones
+/// of a client and may be used from several threads at once.
+pub trait Transport: Debug + Send + Sync {
+    /// Sends `request`, passing the response's header lines and then its
+    /// body to `response` as they arrive, and returns the response code.
+    ///
+    /// A timeout should be reported with `HttpRequest::timed_out`, a
+    /// `response` which refuses data with `HttpRequest::aborted`, and a
+    /// cancelled request with `HttpRequest::cancelled`. Transports should
+    /// notice cancellation while waiting on the daemon, not only between
+    /// reads.
+    fn send(&self, request: &HttpRequest<'_>, response: &mut dyn ResponseSink)
+        -> DockerResult<u32>;
+}
+
+/// Where a transport delivers a response.
+pub trait ResponseSink {
+    /// Receives a header line, terminator included, starting with the status
+    /// line and ending with the blank line after the headers. Returning
+    /// `false` aborts the transfer.
+    fn header(&mut self, line: &[u8]) -> bool;
+
+    /// Receives the next piece of the body. Returning `false` aborts the
+    /// transfer.
+    fn write(&mut self, data: &[u8]) -> bool;
+}
+
+/// A request as it goes over the wire.
+#[derive(Debug, Clone, Copy)]
+pub struct HttpRequest<'a> {
+    pub method: &'a str,
+    /// Host and port of a TCP daemon, or a placeholder host for a socket.
+    pub host: &'a str,
+    pub unix_socket: Option<&'a Path>,
+    pub tls: Option<&'a TlsConfig>,
+    /// The version `path` is prefixed with, such as `1.41`.
+    pub api_version: Option<&'a str>,
+    /// Path and query string, without the API version prefix.
+    pub path: &'a str,
+    pub content_type: Option<&'a str>,
+    pub body: &'a [u8],
+    pub connect_timeout: Option<Duration>,
+    /// Limit on the whole exchange.
+    pub timeout: Option<Duration>,
+    pub cancellation: Option<&'a CancellationToken>,
+}
+impl HttpRequest<'_> {
+    /// The request target: `path` behind its API version prefix.
+    pub fn target(&self) -> String {
+        match self.api_version {
+            Some(api_version) => format!("/v{}{}", api_version, self.path),
+            None => self.path.to_string(),
+        }
+    }
+
+    pub fn url(&self) -> String {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        format!("{}://{}{}", scheme, self.host, self.target())
+    }
+
+    /// The error for a request which ran out of time.
+    pub fn timed_out(&self) -> DockerError {
+        Timeout(format!("{} {}", self.method, self.path))
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// The error for a request which was cancelled.
+    pub fn cancelled(&self) -> DockerError {
+        Cancelled(format!("{} {}", self.method, self.path))
+    }
+
+    /// The error for a transfer the response sink refused to continue.
+    pub fn aborted(&self) -> DockerError {
+        IoError(io::Error::new(
+            io::ErrorKind::Interrupted,
+            format!("{} {} was aborted by its handler", self.method, self.path),
+        ))
+    }
+}
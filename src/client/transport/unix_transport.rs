@@ -0,0 +1,386 @@
+use crate::client::cancellation::CancellationToken;
+use crate::client::transport::{HttpRequest, ResponseSink, Transport};
+use crate::error::DockerError::{IoError, UnsupportedDockerHostError};
+use crate::error::DockerResult;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a cancellable request checks for cancellation while waiting
+/// on the daemon.
+const CANCELLATION_POLL: Duration = Duration::from_millis(50);
+
+/// Speaks HTTP/1.1 to the daemon's Unix socket in plain Rust, and is the
+/// default transport when the `curl` feature is disabled. Each request opens
+/// a connection of its own; TCP and TLS endpoints are not supported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnixTransport;
+impl UnixTransport {
+    pub fn new() -> Self {
+        Self
+    }
+}
+impl Transport for UnixTransport {
+    fn send(
+        &self,
+        request: &HttpRequest<'_>,
+        response: &mut dyn ResponseSink,
+    ) -> DockerResult<u32> {
+        let socket = request.unix_socket.ok_or_else(|| {
+            UnsupportedDockerHostError(format!(
+                "{} is not a Unix socket, which is all UnixTransport can reach",
+                request.host
+            ))
+        })?;
+        let deadline = request.timeout.map(|timeout| Instant::now() + timeout);
+        let io_error = |error: io::Error| match error.kind() {
+            _ if request.is_cancelled() => request.cancelled(),
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => request.timed_out(),
+            _ => IoError(error),
+        };
+
+        let connect_timeout = request
+            .connect_timeout
+            .into_iter()
+            .chain(remaining(deadline).map_err(io_error)?)
+            .min();
+        let mut stream = connect(socket, connect_timeout).map_err(io_error)?;
+        stream.set_write_timeout(remaining(deadline).map_err(io_error)?)?;
+        stream
+            .write_all(&head(request))
+            .and_then(|_| stream.write_all(request.body))
+            .map_err(io_error)?;
+
+        let mut reader = BufReader::new(Deadline {
+            stream,
+            deadline,
+            cancellation: request.cancellation,
+        });
+        read_response(&mut reader, response).map_err(|error| match error {
+            Failure::Io(error) => io_error(error),
+            Failure::Aborted => request.aborted(),
+        })
+    }
+}
+
+/// Connects to `socket`, giving up after `timeout`.
+fn connect(socket: &Path, timeout: Option<Duration>) -> io::Result<UnixStream> {
+    let socket = socket.to_path_buf();
+    within(timeout, move || UnixStream::connect(socket))
+}
+
+/// Runs `connect`, giving up on it after `timeout`. `UnixStream` has no
+/// `connect_timeout`, so a bounded connect is made on a thread of its own,
+/// and a connection it makes after the caller has given up is dropped.
+fn within<F>(timeout: Option<Duration>, connect: F) -> io::Result<UnixStream>
+where
+    F: FnOnce() -> io::Result<UnixStream> + Send + 'static,
+{
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return connect(),
+    };
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(connect());
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(_) => Err(io::ErrorKind::TimedOut.into()),
+    }
+}
+
+/// The request line and headers of `request`.
+fn head(request: &HttpRequest<'_>) -> Vec<u8> {
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: dockurl\r\nConnection: close\r\n",
+        request.method,
+        request.target(),
+        request.host
+    );
+    if let Some(content_type) = request.content_type {
+        head.push_str(&format!("Content-Type: {}\r\n", content_type));
+    }
+    if request.method == "POST" || !request.body.is_empty() {
+        head.push_str(&format!("Content-Length: {}\r\n", request.body.len()));
+    }
+    head.push_str("\r\n");
+    head.into_bytes()
+}
+
+enum Failure {
+    Io(io::Error),
+    /// The response sink refused data.
+    Aborted,
+}
+impl From<io::Error> for Failure {
+    fn from(error: io::Error) -> Self {
+        Failure::Io(error)
+    }
+}
+
+fn read_response<R: BufRead>(
+    reader: &mut R,
+    response: &mut dyn ResponseSink,
+) -> Result<u32, Failure> {
+    let mut content_length = None;
+    let mut chunked = false;
+    let status = loop {
+        let line = read_line(reader)?;
+        let status = std::str::from_utf8(&line)
+            .ok()
+            .filter(|line| line.starts_with("HTTP/"))
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse::<u32>().ok())
+            .ok_or_else(|| invalid("malformed status line"))?;
+        deliver_header(response, &line)?;
+
+        loop {
+            let line = read_line(reader)?;
+            deliver_header(response, &line)?;
+            let header = String::from_utf8_lossy(&line);
+            match header.split_once(':') {
+                Some((name, value)) if name.eq_ignore_ascii_case("Content-Length") => {
+                    content_length = Some(
+                        value
+                            .trim()
+                            .parse::<u64>()
+                            .map_err(|_| invalid("malformed Content-Length"))?,
+                    );
+                }
+                Some((name, value)) if name.eq_ignore_ascii_case("Transfer-Encoding") => {
+                    chunked = value.trim().eq_ignore_ascii_case("chunked");
+                }
+                None if header.trim().is_empty() => break,
+                _ => {}
+            }
+        }
+
+        // Interim responses are followed by the real one; a switch of
+        // protocols (attach) is not.
+        if status >= 200 || status == 101 {
+            break status;
+        }
+        content_length = None;
+        chunked = false;
+    };
+
+    if status == 204 || status == 304 {
+        return Ok(status);
+    }
+    if status != 101 && chunked {
+        loop {
+            let line = read_line(reader)?;
+            let size = String::from_utf8_lossy(&line);
+            let size = size.split(';').next().unwrap_or_default().trim();
+            let size =
+                u64::from_str_radix(size, 16).map_err(|_| invalid("malformed chunk size"))?;
+            if size == 0 {
+                // Skip any trailers.
+                while !read_line(reader)?.iter().all(u8::is_ascii_whitespace) {}
+                break;
+            }
+            copy(reader, Some(size), response)?;
+            read_line(reader)?;
+        }
+    } else if status != 101 && content_length.is_some() {
+        copy(reader, content_length, response)?;
+    } else {
+        copy(reader, None, response)?;
+    }
+
+    Ok(status)
+}
+
+fn deliver_header(response: &mut dyn ResponseSink, line: &[u8]) -> Result<(), Failure> {
+    if response.header(line) {
+        Ok(())
+    } else {
+        Err(Failure::Aborted)
+    }
+}
+
+/// Passes `length` bytes of body, or everything up to the end of the stream,
+/// on to `response`.
+fn copy<R: Read>(
+    reader: &mut R,
+    length: Option<u64>,
+    response: &mut dyn ResponseSink,
+) -> Result<(), Failure> {
+    let mut buffer = [0; 16 * 1024];
+    let mut left = length;
+    while left != Some(0) {
+        let want = left.map_or(buffer.len(), |left| left.min(buffer.len() as u64) as usize);
+        let read = reader.read(&mut buffer[..want])?;
+        if read == 0 {
+            return match left {
+                Some(_) => Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                None => Ok(()),
+            };
+        }
+        if !response.write(&buffer[..read]) {
+            return Err(Failure::Aborted);
+        }
+        left = left.map(|left| left - read as u64);
+    }
+    Ok(())
+}
+
+/// Reads a line, terminator included.
+fn read_line<R: BufRead>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut line = Vec::new();
+    if reader.read_until(b'\n', &mut line)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(line)
+}
+
+fn invalid(message: &str) -> Failure {
+    Failure::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// How long is left until `deadline`, or an error once it has passed.
+fn remaining(deadline: Option<Instant>) -> io::Result<Option<Duration>> {
+    match deadline {
+        Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+            Some(left) if left > Duration::ZERO => Ok(Some(left)),
+            _ => Err(io::ErrorKind::TimedOut.into()),
+        },
+        None => Ok(None),
+    }
+}
+
+/// A stream whose reads give up once the deadline for the request passes,
+/// or once the request is cancelled.
+struct Deadline<'a> {
+    stream: UnixStream,
+    deadline: Option<Instant>,
+    cancellation: Option<&'a CancellationToken>,
+}
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let token = match self.cancellation {
+            Some(token) => token,
+            None => {
+                self.stream.set_read_timeout(remaining(self.deadline)?)?;
+                return self.stream.read(buf);
+            }
+        };
+
+        // Wait in short slices so that cancellation is noticed while the
+        // daemon is silent.
+        loop {
+            if token.is_cancelled() {
+                return Err(io::Error::other("cancelled"));
+            }
+            let wait = remaining(self.deadline)?
+                .map_or(CANCELLATION_POLL, |left| left.min(CANCELLATION_POLL));
+            self.stream.set_read_timeout(Some(wait))?;
+            match self.stream.read(buf) {
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) => {}
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::transport::unix_transport::within;
+    use crate::client::transport::UnixTransport;
+    use crate::error::DockerError;
+    use crate::output::Discard;
+    use crate::testing::{MockDaemon, MockResponse, SilentDaemon};
+    use std::io;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn talks_to_the_daemon_without_curl() {
+        let daemon = MockDaemon::start().unwrap();
+        daemon
+            .route(
+                "GET",
+                "/_ping",
+                MockResponse::new(200)
+                    .header("API-Version", "1.40")
+                    .header("OSType", "linux")
+                    .body(b"OK"),
+            )
+            .route(
+                "POST",
+                "/images/create",
+                MockResponse::stream(
+                    200,
+                    &[
+                        "{\"status\":\"Pulling from library/alpine\"}\r\n{\"err",
+                        "or\":\"manifest unknown\"}\r\n",
+                    ],
+                ),
+            )
+            .route(
+                "DELETE",
+                "/networks/*",
+                MockResponse::json(404, r#"{"message":"network bench not found"}"#),
+            );
+
+        let client = daemon
+            .client()
+            .with_transport(UnixTransport::new())
+            .with_connect_timeout(Duration::from_secs(5));
+        assert_eq!(client.negotiate_api_version().unwrap(), "1.40");
+        assert_eq!(client.ping().unwrap().os_type.as_deref(), Some("linux"));
+
+        let error = client.create_image("alpine", "nope", Discard).unwrap_err();
+        assert_eq!(error.api_error().unwrap().message, "manifest unknown");
+
+        let error = client.delete_network("bench", Discard).unwrap_err();
+        assert!(error.is_not_found());
+        assert_eq!(
+            error.api_error().unwrap().message,
+            "network bench not found"
+        );
+
+        let requests = daemon.requests();
+        assert_eq!(requests[2].api_version.as_deref(), Some("1.40"));
+        assert_eq!(requests[2].header("Content-Length"), Some("0"));
+    }
+
+    #[test]
+    fn times_out() {
+        let daemon = SilentDaemon::start().unwrap();
+        let client = daemon
+            .client()
+            .with_transport(UnixTransport::new())
+            .with_timeout(Duration::from_millis(200));
+        match client.ping() {
+            Err(DockerError::Timeout(request)) => assert_eq!(request, "GET /_ping"),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn gives_up_on_a_connect_which_hangs() {
+        let started = Instant::now();
+        let error = within(Some(Duration::from_millis(100)), || {
+            thread::sleep(Duration::from_secs(2));
+            Err(io::ErrorKind::ConnectionRefused.into())
+        })
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let error = within(Some(Duration::from_secs(5)), || {
+            Err(io::ErrorKind::ConnectionRefused.into())
+        })
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionRefused);
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt::{self, Display};
+
+/// The path and query string of a request, without the API version prefix.
+///
+/// Every path segment and query value is percent-encoded as it is added, so
+/// ids, names and image references reach the daemon as they were given:
+/// `registry:5000/org/img:tag` stays one segment or one value rather than
+/// splitting the path or ending the query parameter early.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Url {
+    path: String,
+    query: String,
+}
+impl Url {
+    /// Starts a URL at `path`, which is taken as it is; anything which is
+    /// not fixed goes in with `segment`.
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            query: String::new(),
+        }
+    }
+
+    /// Appends `/` and `segment`, encoded.
+    pub fn segment(mut self, segment: &str) -> Self {
+        self.path.push('/');
+        self.path.push_str(&encode(segment));
+        self
+    }
+
+    /// Appends the query parameter `name`, with `value` encoded.
+    pub fn query<V: Display>(mut self, name: &str, value: V) -> Self {
+        self.query
+            .push_str(if self.query.is_empty() { "?" } else { "&" });
+        self.query.push_str(&encode(name));
+        self.query.push('=');
+        self.query.push_str(&encode(&value.to_string()));
+        self
+    }
+
+    /// Appends the query parameter `name` if there is a `value`.
+    pub fn query_opt<V: Display>(self, name: &str, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.query(name, value),
+            None => self,
+        }
+    }
+}
+impl Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.path, self.query)
+    }
+}
+impl From<Url> for String {
+    fn from(url: Url) -> Self {
+        url.to_string()
+    }
+}
+
+/// Percent-encodes `value` for use in a URL, leaving only the characters
+/// RFC 3986 calls unreserved as they are.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::url::{encode, Url};
+    use crate::output::Discard;
+    use crate::testing::{MockResponse, MockTransport};
+    use std::path::Path;
+
+    #[test]
+    fn encodes_everything_but_unreserved_characters() {
+        assert_eq!(encode("abc-1.2_3~"), "abc-1.2_3~");
+        assert_eq!(
+            encode(r#"{"label":["a=b c"]}"#),
+            "%7B%22label%22%3A%5B%22a%3Db%20c%22%5D%7D"
+        );
+        assert_eq!(encode("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn builds_paths_and_query_strings() {
+        assert_eq!(Url::new("/_ping").to_string(), "/_ping");
+        assert_eq!(
+            Url::new("/images")
+                .segment("registry:5000/org/img:tag")
+                .query("force", false)
+                .query_opt("limit", None::<u32>)
+                .query_opt("tag", Some("a&b=c"))
+                .to_string(),
+            "/images/registry%3A5000%2Forg%2Fimg%3Atag?force=false&tag=a%26b%3Dc"
+        );
+    }
+
+    #[test]
+    fn encodes_references_and_paths_in_requests() {
+        let transport = MockTransport::new();
+        transport
+            .route("POST", "/images/create", MockResponse::stream(200, &[""]))
+            .route(
+                "POST",
+                "/build",
+                MockResponse::stream(200, &[r#"{"aux":{"ID":"sha256:e821df6f41ad"}}"#]),
+            )
+            .route(
+                "DELETE",
+                "/images/*",
+                MockResponse::json(200, r#"[{"Untagged":"registry:5000/org/img:tag"}]"#),
+            )
+            .route("POST", "/containers/*/start", MockResponse::new(204));
+        let client = transport.client();
+
+        client
+            .create_image("registry:5000/org/img", "v1.2+build", Discard)
+            .unwrap();
+        let context = std::env::temp_dir().join(format!("dockurl-url-{}", std::process::id()));
+        std::fs::create_dir_all(&context).unwrap();
+        let result = client.build_image(
+            "registry:5000/org/img:tag",
+            Path::new("docker files/Dockerfile v2"),
+            &context,
+            Discard,
+        );
+        std::fs::remove_dir_all(&context).unwrap();
+        result.unwrap();
+        client
+            .delete_image("registry:5000/org/img:tag", false, false, Discard)
+            .unwrap();
+        client.start_container("web 1", Discard).unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(
+            requests[0].query.as_deref(),
+            Some("fromImage=registry%3A5000%2Forg%2Fimg&tag=v1.2%2Bbuild")
+        );
+        assert_eq!(
+            requests[1].query.as_deref(),
+            Some("dockerfile=docker%20files%2FDockerfile%20v2&t=registry%3A5000%2Forg%2Fimg%3Atag")
+        );
+        assert_eq!(
+            requests[2].path,
+            "/images/registry%3A5000%2Forg%2Fimg%3Atag"
+        );
+        assert_eq!(
+            requests[2].query.as_deref(),
+            Some("force=false&noprune=false")
+        );
+        assert_eq!(requests[3].path, "/containers/web%201/start");
+    }
+}
@@ -0,0 +1,215 @@
+use crate::client::request::Request;
+use crate::client::version_handler::VersionHandler;
+use crate::client::DockerClient;
+use crate::error::DockerError::UnsupportedApiVersionError;
+use crate::error::{DockerResult, Operation};
+use serde::{Deserialize, Serialize};
+
+/// The newest Engine API version dockurl knows how to speak.
+pub const MAX_API_VERSION: &str = "1.41";
+
+/// What the daemon reports from `/_ping`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ping {
+    pub api_version: Option<String>,
+    pub os_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Version {
+    pub version: String,
+    pub api_version: String,
+    #[serde(rename = "MinAPIVersion")]
+    pub min_api_version: Option<String>,
+    pub git_commit: Option<String>,
+    pub go_version: Option<String>,
+    pub os: String,
+    pub arch: String,
+    pub kernel_version: Option<String>,
+}
+
+impl DockerClient {
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/SystemPing)
+    pub fn ping(&self) -> DockerResult<Ping> {
+        let mut handler = VersionHandler::new();
+        let request = Request::get(Operation::SystemPing, "/_ping").unversioned();
+
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(Ping {
+                api_version: handler.api_version,
+                os_type: handler.os_type,
+            }),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/SystemVersion)
+    pub fn version(&self) -> DockerResult<Version> {
+        let mut handler = VersionHandler::new();
+        let request = Request::get(Operation::SystemVersion, "/version").unversioned();
+
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(serde_json::from_slice(&handler.accumulator)?),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Asks the daemon which API version it speaks and prefixes every
+    /// subsequent request of this client and its clones with the highest
+    /// version both sides support. This happens by itself on the first
+    /// request which needs a version, so calling it is only needed to
+    /// negotiate up front or again.
+    ///
+    /// The `API-Version` header from `/_ping` is used when present, otherwise
+    /// `/version` is consulted. Does nothing when a version has been pinned
+    /// with `with_api_version`.
+    ///
+    /// A daemon newer than `MAX_API_VERSION` is asked for its
+    /// `MinAPIVersion`, and one which no longer serves `MAX_API_VERSION`
+    /// fails negotiation with `UnsupportedApiVersionError`.
+    pub fn negotiate_api_version(&self) -> DockerResult<String> {
+        if self.api_version_pinned {
+            if let Some(api_version) = self.api_version() {
+                return Ok(api_version);
+            }
+        }
+
+        let mut version = None;
+        let server_version = match self.ping()?.api_version {
+            Some(api_version) => api_version,
+            None => {
+                let server = self.version()?;
+                let api_version = server.api_version.clone();
+                version = Some(server);
+                api_version
+            }
+        };
+        let api_version = negotiate(&server_version, MAX_API_VERSION);
+
+        if older(api_version, &server_version) {
+            let version = match version {
+                Some(version) => version,
+                None => self.version()?,
+            };
+            if let Some(min_api_version) = version
+                .min_api_version
+                .filter(|min_api_version| older(api_version, min_api_version))
+            {
+                return Err(UnsupportedApiVersionError(format!(
+                    "the daemon requires API version {} or newer, but dockurl speaks at most {}",
+                    min_api_version, MAX_API_VERSION
+                )));
+            }
+        }
+        *self.api_version.lock().unwrap() = Some(api_version.to_string());
+        Ok(api_version.to_string())
+    }
+}
+
+/// Picks the lower of `server` and `client`, preferring `client` when the
+/// server version cannot be parsed.
+fn negotiate<'a>(server: &'a str, client: &'a str) -> &'a str {
+    match (parse(server), parse(client)) {
+        (Some(server_version), Some(client_version)) if server_version < client_version => server,
+        _ => client,
+    }
+}
+
+/// Whether `api_version` is older than `than`; unparsable versions never are.
+fn older(api_version: &str, than: &str) -> bool {
+    matches!((parse(api_version), parse(than)), (Some(version), Some(than)) if version < than)
+}
+
+fn parse(api_version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = api_version.trim_start_matches('v').split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::version::{negotiate, MAX_API_VERSION};
+    use crate::error::DockerError;
+    use crate::output::Discard;
+    use crate::testing::{MockResponse, MockTransport};
+
+    fn daemon(api_version: &str, min_api_version: &str) -> MockTransport {
+        let transport = MockTransport::new();
+        transport
+            .route(
+                "GET",
+                "/_ping",
+                MockResponse::new(200)
+                    .header("API-Version", api_version)
+                    .body(b"OK"),
+            )
+            .route(
+                "GET",
+                "/version",
+                MockResponse::json(
+                    200,
+                    &format!(
+                        r#"{{"Version":"26.1.0","ApiVersion":"{}","MinAPIVersion":"{}","Os":"linux","Arch":"amd64"}}"#,
+                        api_version, min_api_version
+                    ),
+                ),
+            );
+        transport
+    }
+
+    #[test]
+    fn negotiates_the_lower_version() {
+        assert_eq!(negotiate("1.40", "1.41"), "1.40");
+        assert_eq!(negotiate("1.43", "1.41"), "1.41");
+        assert_eq!(negotiate("1.9", "1.41"), "1.9");
+        assert_eq!(negotiate("garbage", "1.41"), "1.41");
+    }
+
+    #[test]
+    fn rejects_daemons_which_dropped_the_negotiated_version() {
+        let client = daemon("1.40", "1.12").client();
+        assert_eq!(client.negotiate_api_version().unwrap(), "1.40");
+
+        let client = daemon("1.45", "1.24").client();
+        assert_eq!(client.negotiate_api_version().unwrap(), MAX_API_VERSION);
+
+        let client = daemon("1.45", "1.44").client();
+        match client.negotiate_api_version() {
+            Err(DockerError::UnsupportedApiVersionError(message)) => assert_eq!(
+                message,
+                "the daemon requires API version 1.44 or newer, but dockurl speaks at most 1.41"
+            ),
+            other => panic!("expected an unsupported version, got {:?}", other),
+        }
+        assert_eq!(client.api_version(), None);
+    }
+    #[test]
+    fn negotiates_on_the_first_versioned_request() {
+        let transport = daemon("1.40", "1.12");
+        transport.route("POST", "/containers/*/stop", MockResponse::new(204));
+        let client = transport.client();
+        let clone = client.clone();
+        assert_eq!(client.api_version(), None);
+
+        client.stop_container("abc", Discard).unwrap();
+        clone.stop_container("def", Discard).unwrap();
+        assert_eq!(clone.api_version().as_deref(), Some("1.40"));
+
+        let requests = transport.requests();
+        let paths: Vec<_> = requests
+            .iter()
+            .map(|request| request.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["/_ping", "/containers/abc/stop", "/containers/def/stop"]
+        );
+        assert_eq!(requests[0].api_version, None);
+        assert_eq!(requests[1].api_version.as_deref(), Some("1.40"));
+        assert_eq!(requests[2].api_version.as_deref(), Some("1.40"));
+    }
+}
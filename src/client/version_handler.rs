@@ -0,0 +1,41 @@
+use crate::output::ResponseHandler;
+use std::io;
+
+/// Collects the `/_ping` headers and `/version` body used when negotiating
+/// the API version.
+pub struct VersionHandler {
+    pub api_version: Option<String>,
+    pub os_type: Option<String>,
+    pub accumulator: Vec<u8>,
+}
+impl VersionHandler {
+    pub fn new() -> Self {
+        Self {
+            api_version: None,
+            os_type: None,
+            accumulator: vec![],
+        }
+    }
+}
+impl ResponseHandler for VersionHandler {
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.accumulator.extend_from_slice(data);
+
+        Ok(())
+    }
+
+    fn header(&mut self, data: &[u8]) -> io::Result<()> {
+        if let Ok(header) = std::str::from_utf8(data) {
+            if let Some((name, value)) = header.split_once(':') {
+                let value = value.trim().to_string();
+                if name.eq_ignore_ascii_case("API-Version") {
+                    self.api_version = Some(value);
+                } else if name.eq_ignore_ascii_case("OSType") {
+                    self.os_type = Some(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -17,6 +17,11 @@ pub struct Ulimit<'a> {
 pub struct HostConfig {
     fields: HashMap<String, Value>,
 }
+impl Default for HostConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl HostConfig {
     pub fn new() -> Self {
         Self {
@@ -27,6 +27,11 @@ impl EndpointsConfig {
 pub struct EndpointSettings {
     fields: HashMap<&'static str, Value>,
 }
+impl Default for EndpointSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl EndpointSettings {
     pub fn new() -> Self {
         Self {
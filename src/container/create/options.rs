@@ -1,5 +1,6 @@
 use crate::container::create::host_config::HostConfig;
 use crate::container::create::networking_config::NetworkingConfig;
+use crate::error::DockerResult;
 use serde_json::json;
 use serde_json::Map;
 use serde_json::Value;
@@ -16,8 +17,8 @@ impl Default for Options {
     }
 }
 impl Options {
-    pub fn to_json(&self) -> String {
-        serde_json::to_string(&self.fields).unwrap()
+    pub fn to_json(&self) -> DockerResult<String> {
+        Ok(serde_json::to_string(&self.fields)?)
     }
 
     pub fn new() -> Self {
@@ -197,6 +198,6 @@ mod tests {
 
         options.tty(true);
 
-        eprintln!("{}", options.to_json());
+        eprintln!("{}", options.to_json().unwrap());
     }
 }
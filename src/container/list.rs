@@ -0,0 +1,181 @@
+use crate::client::url::Url;
+use crate::filters::Filters;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use strum_macros::Display;
+
+/// Which containers `list_containers` lists.
+///
+/// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerList)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListContainersOptions {
+    pub(crate) all: bool,
+    pub(crate) limit: Option<u32>,
+    pub(crate) size: bool,
+    pub(crate) filters: Filters,
+}
+impl ListContainersOptions {
+    /// The running containers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lists stopped containers as well as running ones.
+    pub fn all(&mut self, all: bool) {
+        self.all = all;
+    }
+
+    /// Lists only this many of the most recently created containers,
+    /// stopped ones included.
+    pub fn limit(&mut self, limit: u32) {
+        self.limit = Some(limit);
+    }
+
+    /// Reports the size of each container's files.
+    pub fn size(&mut self, size: bool) {
+        self.size = size;
+    }
+
+    pub fn filters(&mut self, filters: Filters) {
+        self.filters = filters;
+    }
+
+    pub(crate) fn append_query(&self, url: Url) -> Url {
+        let url = url
+            .query("all", self.all as u8)
+            .query("size", self.size as u8)
+            .query_opt("limit", self.limit);
+        self.filters.append_query(url)
+    }
+}
+
+/// The state a container is in.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum ContainerStatus {
+    Created,
+    Restarting,
+    Running,
+    Removing,
+    Paused,
+    Exited,
+    Dead,
+}
+
+/// The result of a container's health checks.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum HealthStatus {
+    Starting,
+    Healthy,
+    Unhealthy,
+    /// The container has no health check.
+    None,
+}
+
+/// A container as `list_containers` reports it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerSummary {
+    pub id: String,
+    /// Names prefixed with a slash, such as `/tfb-server`.
+    pub names: Vec<String>,
+    pub image: String,
+    #[serde(rename = "ImageID")]
+    pub image_id: String,
+    pub command: String,
+    /// When the container was created, in seconds since the epoch.
+    pub created: i64,
+    #[serde(default)]
+    pub ports: Vec<PortSummary>,
+    /// Size of the files the container has changed, when asked for.
+    pub size_rw: Option<i64>,
+    /// Size of all of the container's files, when asked for.
+    pub size_root_fs: Option<i64>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// Such as `running` or `exited`.
+    pub state: String,
+    /// Such as `Up 5 minutes` or `Exited (0) 2 hours ago`.
+    pub status: String,
+    pub network_settings: Option<NetworkSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PortSummary {
+    #[serde(rename = "IP")]
+    pub ip: Option<String>,
+    pub private_port: u16,
+    pub public_port: Option<u16>,
+    #[serde(rename = "Type")]
+    pub protocol: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct NetworkSummary {
+    /// The container's networks by name; their details are left as the
+    /// daemon sent them.
+    #[serde(default)]
+    pub networks: HashMap<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::container::list::ListContainersOptions;
+    use crate::filters::Filters;
+    use crate::output::Discard;
+    use crate::testing::{MockResponse, MockTransport};
+
+    #[test]
+    fn lists_containers() {
+        let transport = MockTransport::new();
+        transport.route(
+            "GET",
+            "/containers/json",
+            MockResponse::json(
+                200,
+                r#"[{
+                    "Id": "8dfafdbc3a40",
+                    "Names": ["/tfb-server"],
+                    "Image": "tfb.test.gemini",
+                    "ImageID": "sha256:d74508fb6632",
+                    "Command": "/start.sh",
+                    "Created": 1600000000,
+                    "Ports": [{"PrivatePort": 8080, "Type": "tcp"}],
+                    "Labels": {"com.techempower.benchmark": "gemini"},
+                    "State": "exited",
+                    "Status": "Exited (137) 5 minutes ago",
+                    "HostConfig": {"NetworkMode": "tfb"},
+                    "NetworkSettings": {"Networks": {"tfb": {"NetworkID": "7ea29fc1412292a2"}}},
+                    "Mounts": []
+                }]"#,
+            ),
+        );
+
+        let mut options = ListContainersOptions::new();
+        options.all(true);
+        options.limit(10);
+        options.filters(Filters::new().label("com.techempower.benchmark"));
+        let containers = transport
+            .client()
+            .list_containers(options, Discard)
+            .unwrap();
+
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].names, vec!["/tfb-server"]);
+        assert_eq!(containers[0].ports[0].private_port, 8080);
+        assert_eq!(containers[0].size_rw, None);
+        assert!(containers[0]
+            .network_settings
+            .as_ref()
+            .unwrap()
+            .networks
+            .contains_key("tfb"));
+        assert_eq!(
+            transport.requests()[0].query.as_deref(),
+            Some("all=1&size=0&limit=10&filters=%7B%22label%22%3A%5B%22com.techempower.benchmark%22%5D%7D")
+        );
+    }
+}
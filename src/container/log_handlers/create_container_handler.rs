@@ -1,42 +1,51 @@
-use curl::easy::{Handler, WriteError};
+use crate::client::line_decoder::LineDecoder;
+use crate::output::{OutputSink, ResponseHandler};
 use serde_json::Value;
+use std::io;
 
-pub struct CreateContainerHandler<H: Handler> {
+pub struct CreateContainerHandler<S: OutputSink> {
     pub container_id: Option<String>,
-    pub error_message: Option<String>,
-    handler: H,
+    lines: LineDecoder,
+    handler: S,
 }
-impl<H: Handler> CreateContainerHandler<H> {
-    pub fn new(handler: H) -> Self {
+impl<S: OutputSink> CreateContainerHandler<S> {
+    pub fn new(handler: S) -> Self {
         Self {
             container_id: None,
-            error_message: None,
+            lines: LineDecoder::new(),
             handler,
         }
     }
-}
-impl<H: Handler> Handler for CreateContainerHandler<H> {
-    fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
-        self.handler.write(data)?;
 
-        if let Ok(logs) = std::str::from_utf8(data) {
-            for line in logs.lines() {
-                if !line.trim().is_empty() {
-                    if let Ok(json) = serde_json::from_str::<Value>(line) {
-                        if !json["Id"].is_null() {
-                            let mut container_id = json["Id"].as_str().unwrap();
-                            container_id = &container_id[0..12];
-                            self.container_id = Some(container_id.to_string());
-                        } else if !json["message"].is_null() {
-                            // fixme - this APPEARS to be how docker communicates error messages.
-                            let error = json["message"].as_str().unwrap().to_string();
-                            self.error_message = Some(error);
-                        }
-                    }
-                }
+    /// Reads a response which did not end with a newline.
+    pub fn finish(&mut self) {
+        if let Some(line) = self.lines.finish() {
+            self.line(&line);
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        if let Ok(json) = serde_json::from_str::<Value>(line) {
+            // An empty id is no id, and leaves `create_container` to fail.
+            if let Some(id) = json["Id"].as_str().filter(|id| !id.is_empty()) {
+                self.container_id = Some(id.get(..12).unwrap_or(id).to_string());
             }
         }
+    }
+}
+impl<S: OutputSink> ResponseHandler for CreateContainerHandler<S> {
+    fn header(&mut self, line: &[u8]) -> io::Result<()> {
+        self.handler.on_header(line)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.handler.on_stdout(data)?;
+
+        self.lines.push(data);
+        while let Some(line) = self.lines.next_line() {
+            self.line(&line);
+        }
 
-        Ok(data.len())
+        Ok(())
     }
 }
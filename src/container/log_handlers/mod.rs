@@ -1,4 +1,48 @@
 pub(crate) mod create_container_handler;
-pub(crate) mod inspect_container_handler;
-pub(crate) mod start_container_handler;
-pub(crate) mod delete_container_handler;
+pub(crate) mod stream_handler;
+
+#[cfg(test)]
+mod tests {
+    use crate::container::log_handlers::create_container_handler::CreateContainerHandler;
+    use crate::output::{Discard, ResponseHandler};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn create_handler_accepts_any_id(id in "\\PC{0,20}") {
+            let mut handler = CreateContainerHandler::new(Discard);
+            let line = format!("{}\n", serde_json::json!({ "Id": id }));
+            prop_assert!(handler.write(line.as_bytes()).is_ok());
+            let expected = Some(id.get(..12).unwrap_or(&id)).filter(|id| !id.is_empty());
+            prop_assert_eq!(handler.container_id.as_deref(), expected);
+        }
+    }
+
+    #[test]
+    fn create_handler_ignores_an_empty_id() {
+        let mut handler = CreateContainerHandler::new(Discard);
+        handler.write(b"{\"Id\":\"\",\"Warnings\":[]}").unwrap();
+        handler.finish();
+        assert_eq!(handler.container_id, None);
+
+        let mut handler = CreateContainerHandler::new(Discard);
+        handler
+            .write(b"{\"Id\":\"e90e34656806c0a7b5f3ee4c3dc8d8e2f1ab\"}\n")
+            .unwrap();
+        assert_eq!(handler.container_id.as_deref(), Some("e90e34656806"));
+    }
+
+    #[test]
+    fn create_handler_reads_responses_split_at_every_offset() {
+        let response = "{\"Id\":\"e90e34656806c0a7b5f3ee4c3dc8d8e2f1ab\",\
+                        \"Warnings\":[\"m\u{e9}moire limit\u{e9}e\"]}\r\n";
+        let bytes = response.as_bytes();
+        for split in 0..=bytes.len() {
+            let mut handler = CreateContainerHandler::new(Discard);
+            handler.write(&bytes[..split]).unwrap();
+            handler.write(&bytes[split..]).unwrap();
+            handler.finish();
+            assert_eq!(handler.container_id.as_deref(), Some("e90e34656806"));
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::container::stream::{Demuxer, StreamKind};
+use crate::output::{OutputSink, ResponseHandler};
+use std::io;
+
+pub struct StreamHandler<S: OutputSink> {
+    pub sink: S,
+    demuxer: Demuxer,
+}
+impl<S: OutputSink> StreamHandler<S> {
+    /// A handler for the output of a container, which is demultiplexed
+    /// unless the container has a TTY.
+    pub fn new(sink: S, tty: bool) -> Self {
+        Self {
+            sink,
+            demuxer: if tty { Demuxer::raw() } else { Demuxer::new() },
+        }
+    }
+
+    /// Passes on what is left of a stream which ended mid-header.
+    pub fn finish(&mut self) {
+        let sink = &mut self.sink;
+        // The call is over, so there is nothing left to abort.
+        let _ = self.demuxer.finish(|kind, data| deliver(sink, kind, data));
+    }
+}
+impl<S: OutputSink> ResponseHandler for StreamHandler<S> {
+    fn header(&mut self, line: &[u8]) -> io::Result<()> {
+        self.sink.on_header(line)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        let sink = &mut self.sink;
+        self.demuxer
+            .push(data, |kind, data| deliver(sink, kind, data))
+    }
+}
+
+fn deliver<S: OutputSink>(sink: &mut S, kind: StreamKind, data: &[u8]) -> io::Result<()> {
+    match kind {
+        StreamKind::Stderr => sink.on_stderr(data),
+        StreamKind::Stdin | StreamKind::Stdout => sink.on_stdout(data),
+    }
+}
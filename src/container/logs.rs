@@ -0,0 +1,338 @@
+use crate::client::line_decoder::LineDecoder;
+use crate::client::url::Url;
+use crate::container::stream::StreamKind;
+use crate::output::OutputSink;
+use std::convert::TryFrom;
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How many lines from the end of the logs to start at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tail {
+    All,
+    Lines(u64),
+}
+
+/// Which logs `get_container_logs_with_options` gets, and how.
+///
+/// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerLogs)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogsOptions {
+    follow: bool,
+    stdout: bool,
+    stderr: bool,
+    since: Option<SystemTime>,
+    until: Option<SystemTime>,
+    pub(crate) timestamps: bool,
+    tail: Tail,
+    pub(crate) tty: Option<bool>,
+}
+impl Default for LogsOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl LogsOptions {
+    /// Everything the container has written to stdout and stderr so far.
+    pub fn new() -> Self {
+        Self {
+            follow: false,
+            stdout: true,
+            stderr: true,
+            since: None,
+            until: None,
+            timestamps: false,
+            tail: Tail::All,
+            tty: None,
+        }
+    }
+
+    /// Keeps streaming new output until the container stops.
+    pub fn follow(&mut self, follow: bool) {
+        self.follow = follow;
+    }
+
+    pub fn stdout(&mut self, stdout: bool) {
+        self.stdout = stdout;
+    }
+
+    pub fn stderr(&mut self, stderr: bool) {
+        self.stderr = stderr;
+    }
+
+    pub fn since(&mut self, since: SystemTime) {
+        self.since = Some(since);
+    }
+
+    pub fn until(&mut self, until: SystemTime) {
+        self.until = Some(until);
+    }
+
+    /// Prefixes every line with the time it was written, which
+    /// `get_container_log_lines` parses into `LogLine::timestamp`.
+    pub fn timestamps(&mut self, timestamps: bool) {
+        self.timestamps = timestamps;
+    }
+
+    pub fn tail(&mut self, tail: Tail) {
+        self.tail = tail;
+    }
+
+    /// Whether the container has a TTY, as its `ContainerConfig.tty`
+    /// reports, in which case its output is passed on as stdout. Without
+    /// it, the container is inspected to find out.
+    pub fn tty(&mut self, tty: bool) {
+        self.tty = Some(tty);
+    }
+
+    pub(crate) fn append_query(&self, url: Url) -> Url {
+        let url = url
+            .query("follow", self.follow as u8)
+            .query("stdout", self.stdout as u8)
+            .query("stderr", self.stderr as u8)
+            .query("timestamps", self.timestamps as u8)
+            .query_opt("since", self.since.map(unix_time))
+            .query_opt("until", self.until.map(unix_time));
+        match self.tail {
+            Tail::All => url.query("tail", "all"),
+            Tail::Lines(lines) => url.query("tail", lines),
+        }
+    }
+}
+
+/// A line of a container's logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub stream: StreamKind,
+    /// When the line was written, if the logs were asked for with
+    /// timestamps.
+    pub timestamp: Option<SystemTime>,
+    /// The line, without its timestamp or line ending.
+    pub message: String,
+}
+
+/// Splits the output of each stream into lines for a closure.
+pub(crate) struct LineSink<F: FnMut(&LogLine)> {
+    stdout: LineDecoder,
+    stderr: LineDecoder,
+    timestamps: bool,
+    on_line: F,
+}
+impl<F: FnMut(&LogLine)> LineSink<F> {
+    pub fn new(timestamps: bool, on_line: F) -> Self {
+        Self {
+            stdout: LineDecoder::new(),
+            stderr: LineDecoder::new(),
+            timestamps,
+            on_line,
+        }
+    }
+
+    /// Passes on the last line of each stream when it had no newline.
+    pub fn finish(&mut self) {
+        if let Some(line) = self.stdout.finish() {
+            self.line(StreamKind::Stdout, line);
+        }
+        if let Some(line) = self.stderr.finish() {
+            self.line(StreamKind::Stderr, line);
+        }
+    }
+
+    fn line(&mut self, stream: StreamKind, line: String) {
+        let parsed = match line.split_once(' ') {
+            Some((timestamp, message)) if self.timestamps => {
+                parse_timestamp(timestamp).map(|timestamp| (timestamp, message.to_string()))
+            }
+            _ => None,
+        };
+        let line = match parsed {
+            Some((timestamp, message)) => LogLine {
+                stream,
+                timestamp: Some(timestamp),
+                message,
+            },
+            None => LogLine {
+                stream,
+                timestamp: None,
+                message: line,
+            },
+        };
+        (self.on_line)(&line);
+    }
+}
+impl<F: FnMut(&LogLine)> OutputSink for LineSink<F> {
+    fn on_stdout(&mut self, data: &[u8]) -> io::Result<()> {
+        self.stdout.push(data);
+        while let Some(line) = self.stdout.next_line() {
+            self.line(StreamKind::Stdout, line);
+        }
+        Ok(())
+    }
+
+    fn on_stderr(&mut self, data: &[u8]) -> io::Result<()> {
+        self.stderr.push(data);
+        while let Some(line) = self.stderr.next_line() {
+            self.line(StreamKind::Stderr, line);
+        }
+        Ok(())
+    }
+}
+
+/// Seconds since the epoch, as the daemon takes them for `since` and `until`.
+fn unix_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!(
+        "{}.{:09}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_nanos()
+    )
+}
+
+/// Parses an RFC 3339 timestamp such as `2020-06-01T12:30:00.123456789Z`,
+/// the form the daemon prefixes log lines with.
+fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let (date, time) = timestamp.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+
+    let (time, offset) = if let Some(time) = time.strip_suffix('Z') {
+        (time, 0)
+    } else {
+        let at = time.rfind(['+', '-'])?;
+        let (time, offset) = time.split_at(at);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = offset[1..].split_once(':')?;
+        (
+            time,
+            sign * (hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60),
+        )
+    };
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':').map(str::parse::<i64>);
+    let (hour, minute, second) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else if fraction.len() <= 9 && fraction.bytes().all(|b| b.is_ascii_digit()) {
+        format!("{:0<9}", fraction).parse::<u32>().ok()?
+    } else {
+        return None;
+    };
+
+    let seconds =
+        days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+    let seconds = u64::try_from(seconds).ok()?;
+    Some(UNIX_EPOCH + Duration::new(seconds, nanos))
+}
+
+/// Days from 1970-01-01 to the given date of the proleptic Gregorian
+/// calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::url::Url;
+    use crate::container::logs::{parse_timestamp, LogLine, LogsOptions, Tail};
+    use crate::container::stream::StreamKind;
+    use crate::testing::{MockResponse, MockTransport};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn builds_the_query_string() {
+        assert_eq!(
+            LogsOptions::new()
+                .append_query(Url::new("/containers/abc/logs"))
+                .to_string(),
+            "/containers/abc/logs?follow=0&stdout=1&stderr=1&timestamps=0&tail=all"
+        );
+
+        let mut options = LogsOptions::new();
+        options.follow(true);
+        options.stderr(false);
+        options.since(UNIX_EPOCH + Duration::new(1_600_000_000, 500_000_000));
+        options.until(UNIX_EPOCH + Duration::from_secs(1_600_000_060));
+        options.timestamps(true);
+        options.tail(Tail::Lines(100));
+        assert_eq!(
+            options
+                .append_query(Url::new("/containers/abc/logs"))
+                .to_string(),
+            "/containers/abc/logs?follow=1&stdout=1&stderr=0&timestamps=1\
+             &since=1600000000.500000000&until=1600000060.000000000&tail=100"
+        );
+    }
+
+    #[test]
+    fn parses_rfc3339_timestamps() {
+        assert_eq!(
+            parse_timestamp("2020-09-13T12:26:40.123456789Z"),
+            Some(UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_789))
+        );
+        assert_eq!(
+            parse_timestamp("2020-09-13T14:26:40.5+02:00"),
+            Some(UNIX_EPOCH + Duration::new(1_600_000_000, 500_000_000))
+        );
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(UNIX_EPOCH));
+        assert_eq!(
+            parse_timestamp("2000-02-29T00:00:00Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(951_782_400))
+        );
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("2020-13-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn streams_timestamped_lines() {
+        let transport = MockTransport::new();
+        transport.route(
+            "GET",
+            "/containers/*/logs",
+            MockResponse::stream(
+                200,
+                &[
+                    &b"\x01\0\0\0\0\0\0\x282020-09-13T12:26:40Z Listening on :8080\n"[..],
+                    b"\x02\0\0\0\0\0\0\x0cno timestamp",
+                ],
+            ),
+        );
+
+        let mut options = LogsOptions::new();
+        options.follow(true);
+        options.timestamps(true);
+        options.tty(false);
+        let mut lines = vec![];
+        transport
+            .client()
+            .get_container_log_lines("abc", options, |line| lines.push(line.clone()))
+            .unwrap();
+
+        assert_eq!(
+            lines,
+            vec![
+                LogLine {
+                    stream: StreamKind::Stdout,
+                    timestamp: Some(UNIX_EPOCH + Duration::from_secs(1_600_000_000)),
+                    message: "Listening on :8080".to_string(),
+                },
+                LogLine {
+                    stream: StreamKind::Stderr,
+                    timestamp: None,
+                    message: "no timestamp".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            transport.requests()[0].query.as_deref(),
+            Some("follow=1&stdout=1&stderr=1&timestamps=1&tail=all")
+        );
+    }
+}
@@ -1,339 +1,476 @@
 pub mod create;
 pub mod inspect;
+pub mod list;
 mod log_handlers;
+pub mod logs;
+pub mod stream;
+pub mod wait;
 
+use crate::client::request::Request;
+use crate::client::url::Url;
+use crate::client::DockerClient;
 use crate::container::create::options::Options;
 use crate::container::inspect::ContainerInspection;
+use crate::container::list::{ContainerSummary, ListContainersOptions};
 use crate::container::log_handlers::create_container_handler::CreateContainerHandler;
-use crate::container::log_handlers::delete_container_handler::DeleteContainerHandler;
-use crate::container::log_handlers::inspect_container_handler::InspectContainerHandler;
-use crate::container::log_handlers::start_container_handler::StartContainerHandler;
-use crate::error::DockerError::{
-    ContainerInspectionError, ContainerInspectionRequestError, CurlError,
-    DockerContainerCreateError, DockerContainerDeleteBadParameterError,
-    DockerContainerDeleteConflictError, DockerContainerDeleteInternalServerError,
-    DockerContainerDeleteNoSuchContainer, DockerContainerDeleteUnknownError,
-    DockerContainerStartError, DockerDaemonError, FailedToCreateDockerContainerError,
-    FailedToStartDockerContainerError, KillContainerError, NoSuchContainerError,
-    StopContainerError,
-};
-use crate::error::{DockerError, DockerResult};
-use curl::easy::{Easy2, Handler, List};
+use crate::container::log_handlers::stream_handler::StreamHandler;
+use crate::container::logs::{LineSink, LogLine, LogsOptions};
+use crate::container::wait::{WaitOptions, WaitResult};
+use crate::error::{DockerResult, Operation};
+use crate::output::{AccumulatingHandler, Discard, OutputHandler, OutputSink};
+use serde_json::Value;
+
+impl DockerClient {
+    /// Attaches to the output of a container, passing its stdout and stderr
+    /// to the matching methods of `log_handler`. The container is inspected
+    /// first to find out whether it has a TTY, whose output is passed on as
+    /// stdout.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerAttach)
+    pub fn attach_to_container<S: OutputSink>(
+        &self,
+        container_id: &str,
+        log_handler: S,
+    ) -> DockerResult<S> {
+        let tty = self.container_tty(container_id)?;
+        self.attach_to_container_with_tty(container_id, tty, log_handler)
+    }
+
+    /// Attaches to the output of a container like `attach_to_container`,
+    /// without inspecting it. `tty` must be the container's
+    /// `ContainerConfig.tty`, as reported by `inspect_container`.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerAttach)
+    pub fn attach_to_container_with_tty<S: OutputSink>(
+        &self,
+        container_id: &str,
+        tty: bool,
+        log_handler: S,
+    ) -> DockerResult<S> {
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .segment("attach")
+            .query("logs", 1)
+            .query("stream", 1)
+            .query("stdout", 1)
+            .query("stderr", 1);
+        let request = Request::post(Operation::ContainerAttach, url)
+            .resource(container_id)
+            .long_running();
+
+        let mut handler = StreamHandler::new(log_handler, tty);
+        let response = self.execute(&request, &mut handler)?;
+        handler.finish();
+        match response.status {
+            101 | 200 => Ok(handler.sink),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Gets the logs of a container, passing its stdout and stderr to the
+    /// matching methods of `log_handler`. The container is inspected first
+    /// to find out whether it has a TTY, whose output is passed on as stdout.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerLogs)
+    pub fn get_container_logs<S: OutputSink>(
+        &self,
+        container_id: &str,
+        log_handler: S,
+    ) -> DockerResult<S> {
+        self.get_container_logs_with_options(container_id, LogsOptions::new(), log_handler)
+    }
+
+    /// Gets the logs of a container like `get_container_logs`, without
+    /// inspecting it. `tty` must be the container's `ContainerConfig.tty`,
+    /// as reported by `inspect_container`.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerLogs)
+    pub fn get_container_logs_with_tty<S: OutputSink>(
+        &self,
+        container_id: &str,
+        tty: bool,
+        log_handler: S,
+    ) -> DockerResult<S> {
+        let mut options = LogsOptions::new();
+        options.tty(tty);
+        self.get_container_logs_with_options(container_id, options, log_handler)
+    }
+
+    /// Gets the logs of a container selected by `options`. A followed log
+    /// keeps streaming to `log_handler` until the container stops. Unless
+    /// `options` says whether the container has a TTY, it is inspected to
+    /// find out.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerLogs)
+    pub fn get_container_logs_with_options<S: OutputSink>(
+        &self,
+        container_id: &str,
+        options: LogsOptions,
+        log_handler: S,
+    ) -> DockerResult<S> {
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .segment("logs");
+        let request = Request::get(Operation::ContainerLogs, options.append_query(url))
+            .resource(container_id)
+            .long_running();
+
+        let tty = match options.tty {
+            Some(tty) => tty,
+            None => self.container_tty(container_id)?,
+        };
+        let mut handler = StreamHandler::new(log_handler, tty);
+        let response = self.execute(&request, &mut handler)?;
+        handler.finish();
+        match response.status {
+            200 => Ok(handler.sink),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Gets the logs of a container like `get_container_logs_with_options`,
+    /// calling `on_line` with each line as it arrives. Lines carry the time
+    /// they were written when `options` asks for timestamps.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerLogs)
+    pub fn get_container_log_lines<F: FnMut(&LogLine)>(
+        &self,
+        container_id: &str,
+        options: LogsOptions,
+        on_line: F,
+    ) -> DockerResult<()> {
+        let sink = LineSink::new(options.timestamps, on_line);
+        let mut sink = self.get_container_logs_with_options(container_id, options, sink)?;
+        sink.finish();
+        Ok(())
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerCreate)
+    pub fn create_container<S: OutputSink>(
+        &self,
+        options: Options,
+        log_handler: S,
+    ) -> DockerResult<String> {
+        let mut handler = CreateContainerHandler::new(log_handler);
+        let request = Request::post(Operation::ContainerCreate, "/containers/create")
+            .json(options.to_json()?);
+
+        let response = self.execute(&request, &mut handler)?;
+        handler.finish();
+        match response.status {
+            201 => match handler.container_id {
+                Some(container_id) => Ok(container_id),
+                None => Err(request.error_message(201, "the response had no container id")),
+            },
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Lists the containers `options` selects, most recently created first.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerList)
+    pub fn list_containers<S: OutputSink>(
+        &self,
+        options: ListContainersOptions,
+        log_handler: S,
+    ) -> DockerResult<Vec<ContainerSummary>> {
+        let url = options.append_query(Url::new("/containers/json"));
+        let request = Request::get(Operation::ContainerList, url);
+
+        let mut handler = AccumulatingHandler::new(log_handler);
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(serde_json::from_slice(&handler.accumulator)?),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerInspect)
+    pub fn inspect_container<S: OutputSink>(
+        &self,
+        container_id: &str,
+        log_handler: S,
+    ) -> DockerResult<ContainerInspection> {
+        let mut handler = AccumulatingHandler::new(log_handler);
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .segment("json");
+        let request = Request::get(Operation::ContainerInspect, url).resource(container_id);
+
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(serde_json::from_slice(&handler.accumulator)?),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Whether a container has a TTY, as its `ContainerConfig.tty` reports.
+    /// Only that much of the inspection is read, so this does not depend on
+    /// the rest of it matching `ContainerInspection`.
+    fn container_tty(&self, container_id: &str) -> DockerResult<bool> {
+        let mut handler = AccumulatingHandler::new(Discard);
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .segment("json");
+        let request = Request::get(Operation::ContainerInspect, url).resource(container_id);
+
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => {
+                let inspection: Value = serde_json::from_slice(&handler.accumulator)?;
+                Ok(inspection["Config"]["Tty"].as_bool().unwrap_or(false))
+            }
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerStart)
+    pub fn start_container<S: OutputSink>(
+        &self,
+        container_id: &str,
+        log_handler: S,
+    ) -> DockerResult<()> {
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .segment("start");
+        let request = Request::post(Operation::ContainerStart, url)
+            .resource(container_id)
+            .idempotent();
+
+        let mut handler = OutputHandler::new(log_handler);
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            204 => Ok(()),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerStop)
+    pub fn stop_container<S: OutputSink>(
+        &self,
+        container_id: &str,
+        log_handler: S,
+    ) -> DockerResult<()> {
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .segment("stop");
+        let request = Request::post(Operation::ContainerStop, url)
+            .resource(container_id)
+            .idempotent();
+
+        let mut handler = OutputHandler::new(log_handler);
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            204 => Ok(()),
+            304 => Ok(()), // container already stopped
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerKill)
+    pub fn kill_container<S: OutputSink>(
+        &self,
+        container_id: &str,
+        log_handler: S,
+    ) -> DockerResult<()> {
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .segment("kill");
+        let request = Request::post(Operation::ContainerKill, url).resource(container_id);
+
+        let mut handler = OutputHandler::new(log_handler);
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            204 => Ok(()),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerDelete)
+    pub fn delete_container<S: OutputSink>(
+        &self,
+        container_id: &str,
+        log_handler: S,
+        delete_anonymous_volumes: bool,
+        force: bool,
+        remove_associated_link: bool,
+    ) -> DockerResult<()> {
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .query("v", delete_anonymous_volumes)
+            .query("force", force)
+            .query("link", remove_associated_link);
+        let request = Request::delete(Operation::ContainerDelete, url).resource(container_id);
+
+        let mut handler = OutputHandler::new(log_handler);
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            204 => Ok(()),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Waits for a container to stop running, and returns its exit code.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerWait)
+    pub fn wait_for_container_to_exit<S: OutputSink>(
+        &self,
+        container_id: &str,
+        log_handler: S,
+    ) -> DockerResult<WaitResult> {
+        self.wait_for_container_with_options(container_id, WaitOptions::new(), log_handler)
+    }
+
+    /// Waits for a container to meet the condition of `options`, and returns
+    /// its exit code. An exit code other than zero is not an error.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerWait)
+    pub fn wait_for_container_with_options<S: OutputSink>(
+        &self,
+        container_id: &str,
+        options: WaitOptions,
+        log_handler: S,
+    ) -> DockerResult<WaitResult> {
+        let url = Url::new("/containers")
+            .segment(container_id)
+            .segment("wait");
+        let request = Request::post(Operation::ContainerWait, options.append_query(url))
+            .resource(container_id)
+            .long_running()
+            .idempotent()
+            .timeout(options.timeout);
+
+        let mut handler = AccumulatingHandler::new(log_handler);
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(serde_json::from_slice(&handler.accumulator)?),
+            _ => Err(request.error(&response)),
+        }
+    }
+}
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerAttach)
-pub fn attach_to_container<H: Handler + Clone>(
+pub fn attach_to_container<S: OutputSink>(
     container_id: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
-) -> DockerResult<H> {
-    let mut easy = Easy2::new(log_handler);
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    let query_string = "?logs=1&stream=1&stdout=1&stderr=1";
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/containers/{}/attach{}",
-        docker_host, container_id, query_string
-    ))?;
-    easy.perform()?;
-
-    Ok(easy.get_ref().clone())
+    log_handler: S,
+) -> DockerResult<S> {
+    DockerClient::new(docker_host, use_unix_socket).attach_to_container(container_id, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerLogs)
-pub fn get_container_logs<H: Handler + Clone>(
+pub fn get_container_logs<S: OutputSink>(
     container_id: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
-) -> DockerResult<H> {
-    let mut easy = Easy2::new(log_handler);
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    let query_params = "?stdout=1&stderr=1";
-
-    easy.url(&format!(
-        "http://{}/containers/{}/logs{}",
-        docker_host, container_id, query_params,
-    ))?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(code) => match code {
-            200 => Ok(easy.get_ref().clone()),
-            404 => Err(NoSuchContainerError(container_id.to_string())),
-            _ => Err(DockerDaemonError),
-        },
-        Err(e) => Err(CurlError(e)),
-    }
+    log_handler: S,
+) -> DockerResult<S> {
+    DockerClient::new(docker_host, use_unix_socket).get_container_logs(container_id, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerCreate)
-pub fn create_container<H: Handler>(
+pub fn create_container<S: OutputSink>(
     options: Options,
     use_unix_socket: bool,
     docker_host: &str,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<String> {
-    let mut easy = Easy2::new(CreateContainerHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
+    DockerClient::new(docker_host, use_unix_socket).create_container(options, log_handler)
+}
 
-    let mut headers = List::new();
-    headers.append("Content-Type: application/json")?;
-
-    let json = options.to_json();
-    let len = json.as_bytes().len();
-
-    easy.post(true)?;
-    easy.url(&format!("http://{}/containers/create", docker_host))?;
-    easy.http_headers(headers)?;
-    easy.in_filesize(len as u64)?;
-    easy.post_field_size(len as u64)?;
-    easy.post_fields_copy(json.as_bytes())?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(code) => match code {
-            201 => {
-                if let Some(container_id) = &easy.get_mut().container_id {
-                    return Ok(container_id.clone());
-                } else if let Some(error) = &easy.get_ref().error_message {
-                    return Err(FailedToCreateDockerContainerError(error.clone()));
-                }
-                Err(DockerContainerCreateError)
-            }
-            code => {
-                if let Some(error) = &easy.get_ref().error_message {
-                    return Err(FailedToCreateDockerContainerError(error.clone()));
-                }
-                Err(FailedToCreateDockerContainerError(format!("{}", code)))
-            }
-        },
-        Err(e) => Err(FailedToCreateDockerContainerError(e.to_string())),
-    }
+///
+/// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerList)
+pub fn list_containers<S: OutputSink>(
+    options: ListContainersOptions,
+    docker_host: &str,
+    use_unix_socket: bool,
+    log_handler: S,
+) -> DockerResult<Vec<ContainerSummary>> {
+    DockerClient::new(docker_host, use_unix_socket).list_containers(options, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerInspect)
-pub fn inspect_container<H: Handler>(
+pub fn inspect_container<S: OutputSink>(
     container_id: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<ContainerInspection> {
-    let mut easy = Easy2::new(InspectContainerHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.url(&format!(
-        "http://{}/containers/{}/json",
-        docker_host, container_id
-    ))?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(200) => {
-            if let Ok(json_str) = std::str::from_utf8(&easy.get_ref().accumulator) {
-                match serde_json::from_str(json_str) {
-                    Ok(json) => Ok(json),
-                    Err(e) => Err(DockerError::SerdeJsonError(e)),
-                }
-            } else {
-                Err(ContainerInspectionError)
-            }
-        }
-        Ok(404) => Err(ContainerInspectionError),
-        Ok(code) => {
-            if let Some(error) = &easy.get_ref().error_message {
-                return Err(ContainerInspectionRequestError(error.clone(), code));
-            }
-            Err(DockerContainerStartError(code))
-        }
-        Err(e) => Err(DockerError::CurlError(e)),
-    }
+    DockerClient::new(docker_host, use_unix_socket).inspect_container(container_id, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerStart)
-pub fn start_container<H: Handler>(
+pub fn start_container<S: OutputSink>(
     container_id: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<()> {
-    let mut easy = Easy2::new(StartContainerHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/containers/{}/start",
-        docker_host, container_id
-    ))?;
-    easy.post_fields_copy(&[])?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(204) => Ok(()),
-        Ok(code) => {
-            if let Some(error) = &easy.get_ref().error_message {
-                return Err(FailedToStartDockerContainerError(error.clone(), code));
-            }
-            Err(DockerContainerStartError(code))
-        }
-        Err(e) => Err(DockerError::CurlError(e)),
-    }
+    DockerClient::new(docker_host, use_unix_socket).start_container(container_id, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerStop)
-pub fn stop_container<H: Handler>(
+pub fn stop_container<S: OutputSink>(
     container_id: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<()> {
-    let mut easy = Easy2::new(log_handler);
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/containers/{}/stop",
-        docker_host, container_id
-    ))?;
-    easy.perform()?;
-
-    match easy.response_code()? {
-        204 => Ok(()),
-        304 => Ok(()), // container already stopped
-        404 => Err(NoSuchContainerError(container_id.to_string())),
-        _ => Err(StopContainerError(format!(
-            "An error occurred while trying to stop container: {}",
-            container_id
-        ))),
-    }
+    DockerClient::new(docker_host, use_unix_socket).stop_container(container_id, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerKill)
-pub fn kill_container<H: Handler>(
+pub fn kill_container<S: OutputSink>(
     container_id: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<()> {
-    let mut easy = Easy2::new(log_handler);
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/containers/{}/kill",
-        docker_host, container_id
-    ))?;
-    easy.perform()?;
-
-    match easy.response_code()? {
-        204 => Ok(()),
-        _ => Err(KillContainerError(format!(
-            "An error occurred while trying to kill container: {}",
-            container_id
-        ))),
-    }
+    DockerClient::new(docker_host, use_unix_socket).kill_container(container_id, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerDelete)
-pub fn delete_container<H: Handler>(
+pub fn delete_container<S: OutputSink>(
     container_id: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
     delete_anonymous_volumes: bool,
     force: bool,
     remove_associated_link: bool,
 ) -> DockerResult<()> {
-    let mut easy = Easy2::new(DeleteContainerHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.custom_request("DELETE")?;
-    easy.url(&format!(
-        "http://{}/containers/{}?v={}&force={}&link={}",
-        docker_host, container_id, delete_anonymous_volumes, force, remove_associated_link,
-    ))?;
-    easy.perform()?;
-
-    let accumulator = easy.get_ref().accumulator.clone();
-    let response = std::str::from_utf8(&accumulator).unwrap();
-
-    match easy.response_code()? {
-        204 => Ok(()),
-        400 => Err(DockerContainerDeleteBadParameterError(
-            container_id.to_string(),
-            response.to_string(),
-        )),
-        404 => Err(DockerContainerDeleteNoSuchContainer(
-            container_id.to_string(),
-            response.to_string(),
-        )),
-        409 => Err(DockerContainerDeleteConflictError(
-            container_id.to_string(),
-            response.to_string(),
-        )),
-        500 => Err(DockerContainerDeleteInternalServerError(
-            container_id.to_string(),
-            response.to_string(),
-        )),
-        code => Err(DockerContainerDeleteUnknownError(
-            code,
-            container_id.to_string(),
-        )),
-    }
+    DockerClient::new(docker_host, use_unix_socket).delete_container(
+        container_id,
+        log_handler,
+        delete_anonymous_volumes,
+        force,
+        remove_associated_link,
+    )
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerWait)
-pub fn wait_for_container_to_exit<H: Handler>(
+pub fn wait_for_container_to_exit<S: OutputSink>(
     container_id: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
-) -> DockerResult<()> {
-    let mut easy = Easy2::new(log_handler);
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/containers/{}/wait",
-        docker_host, container_id,
-    ))?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(code) => match code {
-            200 => Ok(()),
-            404 => Err(NoSuchContainerError(container_id.to_string())),
-            _ => Err(DockerDaemonError),
-        },
-        Err(e) => Err(CurlError(e)),
-    }
+    log_handler: S,
+) -> DockerResult<WaitResult> {
+    DockerClient::new(docker_host, use_unix_socket)
+        .wait_for_container_to_exit(container_id, log_handler)
 }
@@ -0,0 +1,209 @@
+use std::io;
+
+/// Which of a container's streams a frame of output belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    Stdin,
+    Stdout,
+    Stderr,
+}
+
+/// Splits the multiplexed stream sent for containers without a TTY into the
+/// output of stdout and stderr.
+///
+/// Each frame starts with an 8-byte header: the stream type in the first
+/// byte, three zero bytes, and the length of the payload as a big-endian
+/// `u32`. Frames and headers may be split across any number of writes.
+/// Should a header not look like one, the stream is taken to be the raw
+/// output of a TTY after all and is passed on as stdout from there on.
+///
+/// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerAttach)
+#[derive(Debug, Default)]
+pub struct Demuxer {
+    header: [u8; 8],
+    /// How much of `header` has been read.
+    filled: usize,
+    /// The stream and remaining length of the frame being read.
+    frame: Option<(StreamKind, usize)>,
+    raw: bool,
+}
+impl Demuxer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A demuxer which passes everything on as stdout, for containers with a
+    /// TTY.
+    pub fn raw() -> Self {
+        Self {
+            raw: true,
+            ..Self::default()
+        }
+    }
+
+    /// Passes the payload of the frames in `data` to `deliver` as it arrives,
+    /// stopping at the first error `deliver` returns.
+    pub fn push<F: FnMut(StreamKind, &[u8]) -> io::Result<()>>(
+        &mut self,
+        mut data: &[u8],
+        mut deliver: F,
+    ) -> io::Result<()> {
+        while !data.is_empty() {
+            if self.raw {
+                return deliver(StreamKind::Stdout, data);
+            }
+
+            match self.frame {
+                Some((kind, left)) => {
+                    let length = left.min(data.len());
+                    deliver(kind, &data[..length])?;
+                    data = &data[length..];
+                    self.frame = Some((kind, left - length)).filter(|&(_, left)| left > 0);
+                }
+                None => {
+                    let length = (self.header.len() - self.filled).min(data.len());
+                    self.header[self.filled..self.filled + length].copy_from_slice(&data[..length]);
+                    self.filled += length;
+                    data = &data[length..];
+                    if self.filled < self.header.len() {
+                        break;
+                    }
+
+                    self.filled = 0;
+                    let kind = match self.header[..4] {
+                        [0, 0, 0, 0] => StreamKind::Stdin,
+                        [1, 0, 0, 0] => StreamKind::Stdout,
+                        [2, 0, 0, 0] => StreamKind::Stderr,
+                        _ => {
+                            self.raw = true;
+                            deliver(StreamKind::Stdout, &self.header)?;
+                            continue;
+                        }
+                    };
+                    let mut length = [0; 4];
+                    length.copy_from_slice(&self.header[4..]);
+                    let length = u32::from_be_bytes(length) as usize;
+                    self.frame = Some((kind, length)).filter(|&(_, length)| length > 0);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Passes on the start of a header the stream ended in, which can only
+    /// be the raw output of a TTY shorter than a header.
+    pub fn finish<F: FnMut(StreamKind, &[u8]) -> io::Result<()>>(
+        &mut self,
+        mut deliver: F,
+    ) -> io::Result<()> {
+        let filled = std::mem::take(&mut self.filled);
+        if filled > 0 {
+            deliver(StreamKind::Stdout, &self.header[..filled])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::container::stream::{Demuxer, StreamKind};
+    use crate::output::Collect;
+    use crate::testing::{MockResponse, MockTransport};
+
+    fn demux(demuxer: &mut Demuxer, pieces: &[&[u8]]) -> (Vec<u8>, Vec<u8>) {
+        let (mut stdout, mut stderr) = (vec![], vec![]);
+        let mut deliver = |kind, data: &[u8]| {
+            match kind {
+                StreamKind::Stderr => stderr.extend_from_slice(data),
+                _ => stdout.extend_from_slice(data),
+            }
+            Ok(())
+        };
+        for piece in pieces {
+            demuxer.push(piece, &mut deliver).unwrap();
+        }
+        demuxer.finish(&mut deliver).unwrap();
+        (stdout, stderr)
+    }
+
+    #[test]
+    fn splits_frames_cut_at_every_offset() {
+        let stream = b"\x01\0\0\0\0\0\0\x06hello\n\x02\0\0\0\0\0\0\x05oops\n\x01\0\0\0\0\0\0\0\x01\0\0\0\0\0\0\x03bye";
+        for split in 0..=stream.len() {
+            let (stdout, stderr) =
+                demux(&mut Demuxer::new(), &[&stream[..split], &stream[split..]]);
+            assert_eq!(stdout, b"hello\nbye");
+            assert_eq!(stderr, b"oops\n");
+        }
+    }
+
+    #[test]
+    fn passes_tty_output_through() {
+        let (stdout, stderr) = demux(&mut Demuxer::raw(), &[b"\x01\0\0\0\0\0\0\x01x"]);
+        assert_eq!(stdout, b"\x01\0\0\0\0\0\0\x01x");
+        assert!(stderr.is_empty());
+
+        let (stdout, _) = demux(&mut Demuxer::new(), &[b"Listening on ", b":8080\n"]);
+        assert_eq!(stdout, b"Listening on :8080\n");
+        let (stdout, _) = demux(&mut Demuxer::new(), &[b"ok\n"]);
+        assert_eq!(stdout, b"ok\n");
+    }
+
+    #[test]
+    fn logs_reach_the_sink_demultiplexed() {
+        let transport = MockTransport::new();
+        transport
+            .route_once(
+                "GET",
+                "/containers/*/json",
+                MockResponse::json(200, r#"{"Config":{"Tty":false}}"#),
+            )
+            .route(
+                "GET",
+                "/containers/*/json",
+                MockResponse::json(200, r#"{"Config":{"Tty":true}}"#),
+            )
+            .route(
+                "GET",
+                "/containers/*/logs",
+                MockResponse::stream(
+                    200,
+                    &[
+                        &b"\x01\0\0\0\0\0\0\x06hel"[..],
+                        b"lo\n\x02\0\0",
+                        b"\0\0\0\0\x05oops\n",
+                    ],
+                ),
+            );
+        let client = transport.client();
+
+        let logs = client.get_container_logs("abc", Collect::new()).unwrap();
+        assert_eq!(logs.stdout, b"hello\n");
+        assert_eq!(logs.stderr, b"oops\n");
+
+        // The container now reports a TTY, so its output is passed through.
+        let logs = client.get_container_logs("abc", Collect::new()).unwrap();
+        assert_eq!(logs.stdout.len(), 27);
+        assert!(logs.stderr.is_empty());
+
+        let logs = client
+            .get_container_logs_with_tty("abc", false, Collect::new())
+            .unwrap();
+        assert_eq!(logs.stdout, b"hello\n");
+        let paths: Vec<_> = transport
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/containers/abc/json",
+                "/containers/abc/logs",
+                "/containers/abc/json",
+                "/containers/abc/logs",
+                "/containers/abc/logs",
+            ]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::client::url::Url;
+use serde::Deserialize;
+use std::time::Duration;
+use strum_macros::Display;
+
+/// What to wait for a container to do.
+///
+/// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ContainerWait)
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum WaitCondition {
+    /// Stop running, returning at once if it is not running; the default.
+    NotRunning,
+    /// Exit after it next starts.
+    NextExit,
+    /// Be removed.
+    Removed,
+}
+
+/// How `wait_for_container_with_options` waits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WaitOptions {
+    pub(crate) condition: Option<WaitCondition>,
+    pub(crate) timeout: Option<Duration>,
+}
+impl WaitOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn condition(&mut self, condition: WaitCondition) {
+        self.condition = Some(condition);
+    }
+
+    /// Gives up with `DockerError::Timeout` after `timeout`, in place of the
+    /// client's long-running timeout.
+    pub fn timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    pub(crate) fn append_query(&self, url: Url) -> Url {
+        url.query_opt("condition", self.condition)
+    }
+}
+
+/// How a container exited.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WaitResult {
+    #[serde(rename = "StatusCode")]
+    pub status_code: i64,
+    /// Why waiting failed, such as the container being removed before it
+    /// exited.
+    #[serde(rename = "Error", default, deserialize_with = "error_message")]
+    pub error: Option<String>,
+}
+
+/// Reads `{"Message": "..."}`, treating a missing or empty message as none.
+fn error_message<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    struct WaitError {
+        #[serde(rename = "Message", default)]
+        message: Option<String>,
+    }
+
+    let error: Option<WaitError> = Option::deserialize(deserializer)?;
+    Ok(error
+        .and_then(|error| error.message)
+        .filter(|message| !message.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::container::wait::{WaitCondition, WaitOptions, WaitResult};
+    #[cfg(unix)]
+    use crate::error::DockerError;
+    use crate::output::Discard;
+    #[cfg(unix)]
+    use crate::testing::SilentDaemon;
+    use crate::testing::{MockResponse, MockTransport};
+    use std::time::Duration;
+
+    #[test]
+    fn reads_the_exit_code_and_error() {
+        let result: WaitResult = serde_json::from_str(r#"{"StatusCode":137}"#).unwrap();
+        assert_eq!(
+            result,
+            WaitResult {
+                status_code: 137,
+                error: None
+            }
+        );
+        let result: WaitResult =
+            serde_json::from_str(r#"{"StatusCode":0,"Error":{"Message":""}}"#).unwrap();
+        assert_eq!(result.error, None);
+        let result: WaitResult =
+            serde_json::from_str(r#"{"StatusCode":-1,"Error":{"Message":"container removed"}}"#)
+                .unwrap();
+        assert_eq!(result.error.as_deref(), Some("container removed"));
+    }
+
+    #[test]
+    fn waits_for_the_condition() {
+        let transport = MockTransport::new();
+        transport.route(
+            "POST",
+            "/containers/*/wait",
+            MockResponse::json(200, r#"{"StatusCode":3,"Error":null}"#),
+        );
+        let client = transport.client();
+
+        let mut options = WaitOptions::new();
+        options.condition(WaitCondition::NextExit);
+        options.timeout(Duration::from_secs(60));
+        let result = client
+            .wait_for_container_with_options("abc", options, Discard)
+            .unwrap();
+        assert_eq!(result.status_code, 3);
+        assert_eq!(
+            transport.requests()[0].query.as_deref(),
+            Some("condition=next-exit")
+        );
+
+        assert_eq!(
+            client
+                .wait_for_container_to_exit("abc", Discard)
+                .unwrap()
+                .status_code,
+            3
+        );
+        assert_eq!(transport.requests()[1].query, None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn gives_up_after_the_timeout() {
+        let daemon = SilentDaemon::start().unwrap();
+        let mut options = WaitOptions::new();
+        options.timeout(Duration::from_millis(200));
+        let client = daemon
+            .client()
+            .with_long_running_timeout(Duration::from_secs(600));
+        match client.wait_for_container_with_options("abc", options, Discard) {
+            Err(DockerError::Timeout(request)) => {
+                assert_eq!(request, "POST /containers/abc/wait")
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+}
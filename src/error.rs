@@ -1,11 +1,15 @@
+use std::fmt;
 use std::io;
 
+use serde_json::Value;
+use strum_macros::Display;
 use thiserror::Error;
 
 pub type DockerResult<T> = Result<T, DockerError>;
 
 #[derive(Error, Debug)]
 pub enum DockerError {
+    #[cfg(feature = "curl")]
     #[error("Curl error occurred: {0}")]
     CurlError(#[from] curl::Error),
 
@@ -15,112 +19,205 @@ pub enum DockerError {
     #[error("Serde json error: {0}")]
     SerdeJsonError(#[from] serde_json::error::Error),
 
-    #[error("Error creating Docker Image: {0}")]
-    FailedToCreateDockerImageError(String),
+    #[error("Docker API error: {0}")]
+    ApiError(#[from] ApiError),
 
-    #[error("Error creating Docker Image")]
-    DockerImageCreateError,
+    #[error("Invalid Docker host: {0}")]
+    InvalidDockerHostError(String),
 
-    #[error("Error pulling Docker Image: {0}")]
-    FailedToPullDockerImageError(String),
+    #[error("Unsupported Docker host: {0}")]
+    UnsupportedDockerHostError(String),
 
-    #[error("Error pulling Docker Image")]
-    DockerImagePullError,
+    #[error("Invalid TLS configuration: {0}")]
+    InvalidTlsConfigError(String),
 
-    #[error("Unknown error pruning Docker Image(s)")]
-    DockerImagePruneError,
+    #[error("Unsupported API version: {0}")]
+    UnsupportedApiVersionError(String),
 
-    #[error("Error pruning Docker Image(s): {0}")]
-    FailedToPruneDockerImageError(String),
+    #[error("Docker context not found: {0}")]
+    ContextNotFoundError(String),
 
-    #[error("Unknown error deleting Docker Image(s)")]
-    DockerImageDeleteError,
+    #[error("Request timed out: {0}")]
+    Timeout(String),
 
-    #[error("Error deleting Docker Image(s): {0}")]
-    FailedToDeleteDockerImageError(String),
+    #[error("Gave up after {0} attempts: {1}")]
+    RetriesExhaustedError(u32, Box<DockerError>),
 
-    #[error("Error creating Docker Network: {0}")]
-    FailedToCreateDockerNetworkError(String),
+    #[error("No recorded response for {0}")]
+    NoRecordedResponseError(String),
 
-    #[error("Error creating Docker Network")]
-    DockerNetworkCreateError,
-
-    #[error("Error creating Docker Network; Network already exists: {0}")]
-    DockerNetworkAlreadyExistsCreateError(String),
-
-    #[error("Error deleting Docker Network: {0}")]
-    FailedToDeleteNetworkError(String),
-
-    #[error("Error deleting Docker Network")]
-    DockerNetworkDeleteError,
-
-    #[error("Network not found error: {0}")]
-    NetworkNotFoundError(String),
-
-    #[error("Error inspecting network")]
-    InspectNetworkError,
-
-    #[error("Error attaching Docker Container to Network: {0}")]
-    FailedToAttachDockerContainerToNetworkError(String),
-
-    #[error("Operation not supported for swarm scoped networks")]
-    OperationNotSupportedError,
-
-    #[error("Network or container not found. Network: {0}; Container: {1}")]
-    NetworkOrContainerNotFoundError(String, String),
-
-    #[error("Docker server error")]
-    DockerServerError,
-
-    #[error("Unknown docker server error: {0}")]
-    UnknownDockerError(String),
-
-    #[error("No such container: {0}")]
-    NoSuchContainerError(String),
-
-    #[error("Error stopping Docker Container: {0}")]
-    StopContainerError(String),
-
-    #[error("Error killing Docker Container: {0}")]
-    KillContainerError(String),
-
-    #[error("Error starting Docker Container: {0}; response code {1}")]
-    FailedToStartDockerContainerError(String, u32),
-
-    #[error("Error starting Docker Container; response code {0}")]
-    DockerContainerStartError(u32),
-
-    #[error("Error deleting Docker Container; bad parameter: container id - {0}, message - {1}")]
-    DockerContainerDeleteBadParameterError(String, String),
-
-    #[error(
-        "Error deleting Docker Container; no such container: container id - {0}, message - {1}"
-    )]
-    DockerContainerDeleteNoSuchContainer(String, String),
-
-    #[error("Error deleting Docker Container; conflict: container id - {0}, message - {1}")]
-    DockerContainerDeleteConflictError(String, String),
-
-    #[error(
-        "Error deleting Docker Container; internal server error: container id - {0}, message - {1}"
-    )]
-    DockerContainerDeleteInternalServerError(String, String),
-
-    #[error("Error deleting Docker Container; unknown error: response code - {0}, message - {1}")]
-    DockerContainerDeleteUnknownError(u32, String),
-
-    #[error("Error creating Docker Container: {0}")]
-    FailedToCreateDockerContainerError(String),
-
-    #[error("Error creating Docker Container")]
-    DockerContainerCreateError,
-
-    #[error("Error inspecting Docker Container")]
-    ContainerInspectionError,
+    #[error("Request cancelled: {0}")]
+    Cancelled(String),
+}
+impl DockerError {
+    /// The API error behind this error, looking through retries.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            DockerError::ApiError(error) => Some(error),
+            DockerError::RetriesExhaustedError(_, error) => error.api_error(),
+            _ => None,
+        }
+    }
+
+    /// The response code the daemon answered with, if it answered at all.
+    pub fn status(&self) -> Option<u32> {
+        self.api_error().map(|error| error.status)
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.api_error().is_some_and(ApiError::is_not_found)
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        self.api_error().is_some_and(ApiError::is_conflict)
+    }
+
+    pub fn is_bad_parameter(&self) -> bool {
+        self.api_error().is_some_and(ApiError::is_bad_parameter)
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        self.api_error().is_some_and(ApiError::is_server_error)
+    }
+}
 
-    #[error("Error inspecting Docker Container")]
-    ContainerInspectionRequestError(String, u32),
+/// The Engine API operations dockurl performs, named after their operation
+/// ids in the API reference.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    ContainerAttach,
+    ContainerList,
+    ContainerLogs,
+    ContainerCreate,
+    ContainerInspect,
+    ContainerStart,
+    ContainerStop,
+    ContainerKill,
+    ContainerDelete,
+    ContainerWait,
+    ImageBuild,
+    ImageCreate,
+    ImageDelete,
+    BuildPrune,
+    ImagePrune,
+    NetworkCreate,
+    NetworkConnect,
+    NetworkDelete,
+    NetworkInspect,
+    SystemPing,
+    SystemVersion,
+}
 
-    #[error("Docker Daemon Error")]
-    DockerDaemonError,
+/// An operation the daemon refused or failed to carry out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    /// The HTTP response code. Errors reported in the middle of a stream,
+    /// such as a failing build step, carry the code the stream began with.
+    pub status: u32,
+    /// The daemon's `message`, or the raw response when it sent no JSON.
+    pub message: String,
+    pub operation: Operation,
+    /// The container, image or network id or name the operation acted on.
+    pub resource: Option<String>,
+}
+impl ApiError {
+    pub fn new(
+        operation: Operation,
+        resource: Option<&str>,
+        status: u32,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            operation,
+            resource: resource.map(str::to_string),
+        }
+    }
+
+    /// Builds an error from an error response, taking the message from the
+    /// `{"message": ...}` body the daemon sends.
+    pub fn from_body(
+        operation: Operation,
+        resource: Option<&str>,
+        status: u32,
+        body: &[u8],
+    ) -> Self {
+        let message = match serde_json::from_slice::<Value>(body) {
+            Ok(json) if json["message"].is_string() => {
+                json["message"].as_str().unwrap_or_default().to_string()
+            }
+            _ => String::from_utf8_lossy(body).trim().to_string(),
+        };
+
+        Self::new(operation, resource, status, message)
+    }
+
+    pub fn is_bad_parameter(&self) -> bool {
+        self.status == 400
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status == 404
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        self.status == 409
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        self.status >= 500
+    }
+}
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.operation)?;
+        if let Some(resource) = &self.resource {
+            write!(f, " ({})", resource)?;
+        }
+        write!(f, " failed with response code {}", self.status)?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+impl std::error::Error for ApiError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{ApiError, DockerError, Operation};
+
+    #[test]
+    fn from_body_prefers_the_daemon_message() {
+        let error = ApiError::from_body(
+            Operation::ContainerDelete,
+            Some("abc123"),
+            409,
+            br#"{"message":"You cannot remove a running container abc123"}"#,
+        );
+        assert_eq!(
+            error.message,
+            "You cannot remove a running container abc123"
+        );
+        assert_eq!(
+            error.to_string(),
+            "ContainerDelete (abc123) failed with response code 409: \
+             You cannot remove a running container abc123"
+        );
+
+        let error = ApiError::from_body(Operation::SystemPing, None, 502, b"Bad Gateway\n");
+        assert_eq!(error.message, "Bad Gateway");
+    }
+
+    #[test]
+    fn helpers_look_through_retries() {
+        let error = DockerError::RetriesExhaustedError(
+            3,
+            Box::new(ApiError::new(Operation::ContainerInspect, Some("abc"), 404, "").into()),
+        );
+        assert!(error.is_not_found());
+        assert!(!error.is_conflict());
+        assert_eq!(error.status(), Some(404));
+    }
 }
@@ -0,0 +1,166 @@
+//! The `filters` parameter taken by the endpoints which list and prune.
+
+use crate::client::url::Url;
+use crate::container::list::{ContainerStatus, HealthStatus};
+use std::collections::BTreeMap;
+
+/// Narrows down what an endpoint lists or prunes, as a map of filter names
+/// to values. A filter may be given several values, of which one must
+/// match; every filter given must match.
+///
+/// Not every endpoint takes every filter; the daemon rejects filters an
+/// endpoint does not know with a `400`.
+///
+/// ```
+/// use dockurl::filters::Filters;
+///
+/// let filters = Filters::new()
+///     .label("com.techempower.benchmark")
+///     .label_not("keep")
+///     .until("24h");
+/// assert_eq!(
+///     filters.to_json(),
+///     r#"{"label":["com.techempower.benchmark"],"label!":["keep"],"until":["24h"]}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filters {
+    filters: BTreeMap<String, Vec<String>>,
+}
+impl Filters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value` to the filter `name`, for filters without a helper.
+    pub fn add(mut self, name: &str, value: &str) -> Self {
+        self.filters
+            .entry(name.to_string())
+            .or_default()
+            .push(value.to_string());
+        self
+    }
+
+    /// Objects with the label `key`, or with the label set to a value when
+    /// given as `key=value`.
+    pub fn label(self, label: &str) -> Self {
+        self.add("label", label)
+    }
+
+    /// Objects without the label `key`, or without it set to a value when
+    /// given as `key=value`.
+    pub fn label_not(self, label: &str) -> Self {
+        self.add("label!", label)
+    }
+
+    /// Objects created before `until`: a timestamp such as
+    /// `2020-09-13T12:26:40Z` or seconds since the epoch, or a duration
+    /// before now such as `24h`.
+    pub fn until(self, until: &str) -> Self {
+        self.add("until", until)
+    }
+
+    /// Images which are, or are not, untagged and unused.
+    pub fn dangling(self, dangling: bool) -> Self {
+        self.add("dangling", &dangling.to_string())
+    }
+
+    /// Images whose reference matches `reference`, such as `alpine` or
+    /// `tfb.test.*`.
+    pub fn reference(self, reference: &str) -> Self {
+        self.add("reference", reference)
+    }
+
+    /// Containers whose name contains `name`.
+    pub fn name(self, name: &str) -> Self {
+        self.add("name", name)
+    }
+
+    pub fn status(self, status: ContainerStatus) -> Self {
+        self.add("status", &status.to_string())
+    }
+
+    /// Containers created from `image`, or from an image built on it.
+    pub fn ancestor(self, image: &str) -> Self {
+        self.add("ancestor", image)
+    }
+
+    /// Containers connected to the network with this name or id.
+    pub fn network(self, network: &str) -> Self {
+        self.add("network", network)
+    }
+
+    /// Containers created before the container with this name or id.
+    pub fn before(self, container: &str) -> Self {
+        self.add("before", container)
+    }
+
+    /// Containers created after the container with this name or id.
+    pub fn since(self, container: &str) -> Self {
+        self.add("since", container)
+    }
+
+    pub fn health(self, health: HealthStatus) -> Self {
+        self.add("health", &health.to_string())
+    }
+
+    /// Stopped containers which exited with `exit_code`.
+    pub fn exited(self, exit_code: i64) -> Self {
+        self.add("exited", &exit_code.to_string())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// The filters in the JSON form the daemon takes, such as
+    /// `{"label":["a=b"],"status":["exited"]}`.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.filters).unwrap_or_default()
+    }
+
+    /// Adds the `filters` query parameter to `url`, unless there are no
+    /// filters.
+    pub(crate) fn append_query(&self, url: Url) -> Url {
+        if self.is_empty() {
+            url
+        } else {
+            url.query("filters", self.to_json())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::url::Url;
+    use crate::container::list::{ContainerStatus, HealthStatus};
+    use crate::filters::Filters;
+
+    #[test]
+    fn serializes_to_the_daemon_json() {
+        let filters = Filters::new()
+            .label("com.techempower.benchmark")
+            .label("tier=db")
+            .status(ContainerStatus::Exited)
+            .exited(137)
+            .health(HealthStatus::None)
+            .dangling(true);
+        assert_eq!(
+            filters.to_json(),
+            r#"{"dangling":["true"],"exited":["137"],"health":["none"],"label":["com.techempower.benchmark","tier=db"],"status":["exited"]}"#
+        );
+        assert_eq!(
+            Filters::new()
+                .reference("tfb.test.*")
+                .append_query(Url::new("/images/json"))
+                .to_string(),
+            "/images/json?filters=%7B%22reference%22%3A%5B%22tfb.test.%2A%22%5D%7D"
+        );
+        assert_eq!(
+            Filters::new()
+                .append_query(Url::new("/images/json"))
+                .to_string(),
+            "/images/json"
+        );
+    }
+}
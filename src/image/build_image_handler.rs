@@ -1,55 +1,73 @@
-use curl::easy::{Handler, WriteError};
-use serde_json::Value;
+use crate::client::line_decoder::LineDecoder;
+use crate::image::progress::ProgressEvent;
+use crate::output::{OutputSink, ResponseHandler};
+use std::io;
 
-pub struct BuildImageHandler<H: Handler> {
+pub struct BuildImageHandler<S: OutputSink> {
     pub image_id: Option<String>,
     pub error_message: Option<String>,
-    handler: H,
+    lines: LineDecoder,
+    handler: S,
+    /// Whether `handler` takes the stream as it comes rather than parsed.
+    raw: bool,
 }
-impl<H: Handler> BuildImageHandler<H> {
-    pub fn new(handler: H) -> Self {
+impl<S: OutputSink> BuildImageHandler<S> {
+    pub fn new(handler: S) -> Self {
         Self {
             image_id: None,
             error_message: None,
+            lines: LineDecoder::new(),
+            raw: handler.raw_progress(),
             handler,
         }
     }
-}
-impl<H: Handler> Handler for BuildImageHandler<H> {
-    fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
-        self.handler.write(data)?;
 
-        if let Ok(logs) = std::str::from_utf8(data) {
-            for line in logs.lines() {
-                if !line.trim().is_empty() {
-                    // Docker is sending us lines of json encoded strings on every write.
-                    // These look like:
-                    // {"stream":" ---\u003e Using cache\n"}
-                    // I don't know enough about this API to state definitively that the
-                    // "stream" values are all we care about, but it seems likely. Other
-                    // keys exist, such as:
-                    // {"aux":{"ID":"sha256:e821df6f41ad85f08c5fa08a228a34e164d93995e89be2d0d5edb9206a715347"}}
-                    // which looks like the id of the image that was built. Likely, we
-                    // neither care nor need to log it.
-                    if let Ok(json) = serde_json::from_str::<Value>(line) {
-                        if !json["aux"].is_null() {
-                            let line = json["aux"]["ID"].as_str().unwrap();
-                            // fixme - This is a hack to remove "sha256:" from the string, but
-                            //  it may not always use sha256, so this should be done right.
-                            let sha = &line[7..];
-                            self.image_id = Some(sha.to_string());
-                        } else if !json["error"].is_null() {
-                            let error = json["error"].as_str().unwrap().to_string();
-                            self.error_message = Some(error);
-                        } else if !json["message"].is_null() {
-                            let error = json["message"].as_str().unwrap().to_string();
-                            self.error_message = Some(error);
-                        }
+    /// Reads a stream which did not end with a newline.
+    pub fn finish(&mut self) {
+        if let Some(line) = self.lines.finish() {
+            // The call is over, so there is nothing left to abort.
+            let _ = self.line(&line);
+        }
+    }
+
+    fn line(&mut self, line: &str) -> io::Result<()> {
+        match ProgressEvent::parse(line) {
+            Some(event) => {
+                match &event {
+                    ProgressEvent::Aux(aux) => {
+                        // Image ids are digests such as "sha256:e821df6f...", of
+                        // which only the hex part is kept.
+                        let sha = aux.id.split_once(':').map_or(&*aux.id, |(_, hex)| hex);
+                        self.image_id = Some(sha.to_string());
                     }
+                    ProgressEvent::Error { error, .. } => self.error_message = Some(error.clone()),
+                    _ => {}
+                }
+                if self.raw {
+                    Ok(())
+                } else {
+                    self.handler.on_progress(&event)
                 }
             }
+            None if self.raw => Ok(()),
+            None => self.handler.on_stdout(format!("{}\n", line).as_bytes()),
+        }
+    }
+}
+impl<S: OutputSink> ResponseHandler for BuildImageHandler<S> {
+    fn header(&mut self, line: &[u8]) -> io::Result<()> {
+        self.handler.on_header(line)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.raw {
+            self.handler.on_stdout(data)?;
+        }
+        self.lines.push(data);
+        while let Some(line) = self.lines.next_line() {
+            self.line(&line)?;
         }
 
-        Ok(data.len())
+        Ok(())
     }
 }
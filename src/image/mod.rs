@@ -1,17 +1,18 @@
 mod build_image_handler;
-mod prune_images_handler;
-
-use crate::error::DockerError::{
-    DockerImageCreateError, DockerImageDeleteError, DockerImagePruneError, DockerImagePullError,
-    FailedToCreateDockerImageError, FailedToDeleteDockerImageError, FailedToPruneDockerImageError,
-    FailedToPullDockerImageError,
-};
-use crate::error::DockerResult;
+pub mod progress;
+pub mod prune;
+
+use crate::client::request::Request;
+use crate::client::url::Url;
+use crate::client::DockerClient;
+use crate::error::{DockerResult, Operation};
+use crate::filters::Filters;
 use crate::image::build_image_handler::BuildImageHandler;
-use crate::image::prune_images_handler::PruneImagesHandler;
-use curl::easy::{Easy2, Handler, List};
-use std::io::{Error, Write};
-use std::path::PathBuf;
+use crate::image::progress::ProgressEvent;
+use crate::image::prune::{BuildCachePruneReport, ImageDeleteResponseItem, ImagePruneReport};
+use crate::output::{AccumulatingHandler, OutputSink};
+use std::io::{self, Error, Write};
+use std::path::Path;
 
 // pub struct BuildImageOptions<'a> {
 //     dockerfile: &'a PathBuf,
@@ -39,225 +40,289 @@ use std::path::PathBuf;
 //     outputs: Option<&'a str>,
 // }
 
+impl DockerClient {
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ImageBuild)
+    pub fn build_image<S: OutputSink>(
+        &self,
+        name_and_tag: &str,
+        dockerfile: &Path,
+        context_dir: &Path,
+        log_handler: S,
+    ) -> DockerResult<String> {
+        let handler = BuildImageHandler::new(log_handler);
+        self.build(name_and_tag, dockerfile, context_dir, handler)
+    }
+
+    /// Builds an image like `build_image`, calling `on_event` with each line
+    /// of build output as it arrives.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ImageBuild)
+    pub fn build_image_with_events<F: FnMut(&ProgressEvent)>(
+        &self,
+        name_and_tag: &str,
+        dockerfile: &Path,
+        context_dir: &Path,
+        on_event: F,
+    ) -> DockerResult<String> {
+        let handler = BuildImageHandler::new(Events(on_event));
+        self.build(name_and_tag, dockerfile, context_dir, handler)
+    }
+
+    fn build<S: OutputSink>(
+        &self,
+        name_and_tag: &str,
+        dockerfile: &Path,
+        context_dir: &Path,
+        mut handler: BuildImageHandler<S>,
+    ) -> DockerResult<String> {
+        let mut tarchive = Tarchive(Vec::new());
+        let mut tar = tar::Builder::new(&mut tarchive);
+        tar.append_dir_all("", context_dir)?;
+        tar.finish()?;
+
+        let dockerfile = dockerfile.to_str().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not valid UTF-8", dockerfile.display()),
+            )
+        })?;
+
+        let url = Url::new("/build")
+            .query("dockerfile", dockerfile)
+            .query("t", name_and_tag);
+        let bytes = tar.get_mut().buffer().to_vec();
+
+        let request = Request::post(Operation::ImageBuild, url)
+            .resource(name_and_tag)
+            .body("application/x-tar", bytes)
+            .long_running();
+
+        let response = self.execute(&request, &mut handler)?;
+        handler.finish();
+        match response.status {
+            200 => match (handler.image_id, handler.error_message) {
+                (_, Some(error_message)) => Err(request.error_message(200, error_message)),
+                (Some(image_id), None) => Ok(image_id),
+                (None, None) => Err(request.error_message(200, "the build produced no image id")),
+            },
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ImageCreate)
+    pub fn create_image<S: OutputSink>(
+        &self,
+        from_image: &str,
+        tag: &str,
+        log_handler: S,
+    ) -> DockerResult<()> {
+        let handler = BuildImageHandler::new(log_handler);
+        self.pull(from_image, tag, handler)
+    }
+
+    /// Pulls an image like `create_image`, calling `on_event` with the
+    /// progress of the pull and of each of its layers as it arrives.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ImageCreate)
+    pub fn create_image_with_events<F: FnMut(&ProgressEvent)>(
+        &self,
+        from_image: &str,
+        tag: &str,
+        on_event: F,
+    ) -> DockerResult<()> {
+        let handler = BuildImageHandler::new(Events(on_event));
+        self.pull(from_image, tag, handler)
+    }
+
+    fn pull<S: OutputSink>(
+        &self,
+        from_image: &str,
+        tag: &str,
+        mut handler: BuildImageHandler<S>,
+    ) -> DockerResult<()> {
+        let url = Url::new("/images/create")
+            .query("fromImage", from_image)
+            .query("tag", tag);
+
+        let request = Request::post(Operation::ImageCreate, url)
+            .resource(from_image)
+            .long_running();
+
+        let response = self.execute(&request, &mut handler)?;
+        handler.finish();
+        match response.status {
+            200 => match handler.error_message {
+                Some(error_message) => Err(request.error_message(200, error_message)),
+                None => Ok(()), // todo - do we want to return the image_id?
+            },
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Removes an image, and reports the tags which were removed and the
+    /// images which were deleted.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/ImageDelete)
+    pub fn delete_image<S: OutputSink>(
+        &self,
+        image_name_or_id: &str,
+        force: bool,
+        no_prune: bool,
+        log_handler: S,
+    ) -> DockerResult<Vec<ImageDeleteResponseItem>> {
+        let url = Url::new("/images")
+            .segment(image_name_or_id)
+            .query("force", force)
+            .query("noprune", no_prune);
+
+        let mut handler = AccumulatingHandler::new(log_handler);
+        let request = Request::delete(Operation::ImageDelete, url).resource(image_name_or_id);
+
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(serde_json::from_slice(&handler.accumulator)?),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Deletes build cache, and reports what was deleted and how much disk
+    /// space that freed.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/BuildPrune)
+    pub fn delete_builder_cache<S: OutputSink>(
+        &self,
+        keep_storage: i64,
+        remove_all: bool,
+        filters: &Filters,
+        log_handler: S,
+    ) -> DockerResult<BuildCachePruneReport> {
+        let url = Url::new("/build/prune")
+            .query("keep-storage", keep_storage)
+            .query("all", remove_all);
+        let url = filters.append_query(url);
+
+        let mut handler = AccumulatingHandler::new(log_handler);
+        let request = Request::post(Operation::BuildPrune, url);
+
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(serde_json::from_slice(&handler.accumulator)?),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    /// Deletes unused images, and reports what was deleted and how much disk
+    /// space that freed.
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/ImagePrune)
+    pub fn delete_unused_images<S: OutputSink>(
+        &self,
+        filters: &Filters,
+        log_handler: S,
+    ) -> DockerResult<ImagePruneReport> {
+        let url = filters.append_query(Url::new("/images/prune"));
+
+        let mut handler = AccumulatingHandler::new(log_handler);
+        let request = Request::post(Operation::ImagePrune, url);
+
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(serde_json::from_slice(&handler.accumulator)?),
+            _ => Err(request.error(&response)),
+        }
+    }
+}
+
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ImageBuild)
-pub fn build_image<H: Handler>(
+pub fn build_image<S: OutputSink>(
     name_and_tag: &str,
-    dockerfile: &PathBuf,
-    context_dir: &PathBuf,
+    dockerfile: &Path,
+    context_dir: &Path,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<String> {
-    let mut tarchive = Tarchive(Vec::new());
-    let mut tar = tar::Builder::new(&mut tarchive);
-    tar.append_dir_all("", context_dir.to_str().unwrap())?;
-    tar.finish()?;
-
-    let dockerfile = dockerfile.to_str().unwrap();
-
-    let query_string = format!("?dockerfile={}&t={}", dockerfile, name_and_tag);
-    let mut headers = List::new();
-    headers.append("Content-Type: application/x-tar")?;
-    let bytes = tar.get_mut().buffer();
-    let len = bytes.len();
-
-    let mut easy = Easy2::new(BuildImageHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.post(true)?;
-    easy.http_headers(headers)?;
-    easy.in_filesize(len as u64)?;
-    easy.post_field_size(len as u64)?;
-    easy.url(&format!("http://{}/build{}", docker_host, query_string))?;
-    easy.post_fields_copy(bytes)?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(code) => match code {
-            200 => {
-                if let Some(image_id) = &easy.get_ref().image_id {
-                    return Ok(image_id.to_owned());
-                } else if let Some(error_message) = &easy.get_ref().error_message {
-                    return Err(FailedToCreateDockerImageError(error_message.to_owned()));
-                }
-                Err(DockerImageCreateError)
-            }
-            _ => {
-                if let Some(error_message) = &easy.get_ref().error_message {
-                    return Err(FailedToCreateDockerImageError(error_message.to_owned()));
-                }
-                Err(DockerImageCreateError)
-            }
-        },
-        Err(e) => Err(FailedToCreateDockerImageError(e.to_string())),
-    }
+    DockerClient::new(docker_host, use_unix_socket).build_image(
+        name_and_tag,
+        dockerfile,
+        context_dir,
+        log_handler,
+    )
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/ImageCreate)
-pub fn create_image<H: Handler>(
+pub fn create_image<S: OutputSink>(
     from_image: &str,
     tag: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<()> {
-    let query_string = format!("?fromImage={}&tag={}", from_image, tag);
-
-    let mut easy = Easy2::new(BuildImageHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/images/create{}",
-        docker_host, query_string
-    ))?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(code) => match code {
-            200 => Ok(()), // todo - do we want to return the image_id?
-            _ => {
-                let error_message = &easy.get_ref().error_message;
-                if error_message.is_some() {
-                    return Err(FailedToPullDockerImageError(error_message.clone().unwrap()));
-                }
-                Err(DockerImagePullError)
-            }
-        },
-        Err(e) => Err(FailedToPullDockerImageError(e.to_string())),
-    }
+    DockerClient::new(docker_host, use_unix_socket).create_image(from_image, tag, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/ImageDelete)
-pub fn delete_image<H: Handler>(
+pub fn delete_image<S: OutputSink>(
     image_name_or_id: &str,
     force: bool,
     no_prune: bool,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
-) -> DockerResult<Option<String>> {
-    let query_string = format!("?force={}&noprune={}", force, no_prune);
-
-    let mut easy = Easy2::new(PruneImagesHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.custom_request("DELETE")?;
-    easy.url(&format!(
-        "http://{}/images/{}{}",
-        docker_host, image_name_or_id, query_string
-    ))?;
-    easy.perform()?;
-
-    let message = easy.get_ref().message.clone();
-    let error_message = &easy.get_ref().error_message;
-    if error_message.is_some() {
-        Err(FailedToDeleteDockerImageError(
-            error_message.clone().unwrap(),
-        ))
-    } else {
-        match easy.response_code() {
-            Ok(code) => match code {
-                200 => Ok(message),
-                _ => Err(DockerImageDeleteError),
-            },
-            Err(e) => Err(FailedToDeleteDockerImageError(e.to_string())),
-        }
-    }
+    log_handler: S,
+) -> DockerResult<Vec<ImageDeleteResponseItem>> {
+    DockerClient::new(docker_host, use_unix_socket).delete_image(
+        image_name_or_id,
+        force,
+        no_prune,
+        log_handler,
+    )
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/BuildPrune)
-pub fn delete_builder_cache<H: Handler>(
+pub fn delete_builder_cache<S: OutputSink>(
     keep_storage: i64,
     remove_all: bool,
-    filters: &str,
+    filters: &Filters,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
-) -> DockerResult<()> {
-    let query_string = format!(
-        "?keep-storage={}&all={}&filters={}",
-        keep_storage, remove_all, filters
-    );
-
-    let mut easy = Easy2::new(PruneImagesHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/build/prune{}",
-        docker_host, query_string
-    ))?;
-    easy.perform()?;
-
-    let error_message = &easy.get_ref().error_message;
-    if error_message.is_some() {
-        Err(FailedToPruneDockerImageError(
-            error_message.clone().unwrap(),
-        ))
-    } else {
-        match easy.response_code() {
-            Ok(code) => match code {
-                200 => Ok(()),
-                _ => Err(DockerImagePruneError),
-            },
-            Err(e) => Err(FailedToPruneDockerImageError(e.to_string())),
-        }
-    }
+    log_handler: S,
+) -> DockerResult<BuildCachePruneReport> {
+    DockerClient::new(docker_host, use_unix_socket).delete_builder_cache(
+        keep_storage,
+        remove_all,
+        filters,
+        log_handler,
+    )
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/ImagePrune)
-pub fn delete_unused_images<H: Handler>(
-    filters: &str,
+pub fn delete_unused_images<S: OutputSink>(
+    filters: &Filters,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
-) -> DockerResult<()> {
-    let query_string = format!("?filters={}", filters);
+    log_handler: S,
+) -> DockerResult<ImagePruneReport> {
+    DockerClient::new(docker_host, use_unix_socket).delete_unused_images(filters, log_handler)
+}
 
-    let mut easy = Easy2::new(PruneImagesHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
+// PRIVATES
 
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/images/prune{}",
-        docker_host, query_string
-    ))?;
-    easy.perform()?;
-
-    let error_message = &easy.get_ref().error_message;
-    if error_message.is_some() {
-        Err(FailedToPruneDockerImageError(
-            error_message.clone().unwrap(),
-        ))
-    } else {
-        match easy.response_code() {
-            Ok(code) => match code {
-                200 => Ok(()),
-                _ => Err(DockerImagePruneError),
-            },
-            Err(e) => Err(FailedToPruneDockerImageError(e.to_string())),
-        }
+/// Passes the progress events of a build or pull to a closure.
+struct Events<F: FnMut(&ProgressEvent)>(F);
+impl<F: FnMut(&ProgressEvent)> OutputSink for Events<F> {
+    fn on_progress(&mut self, event: &ProgressEvent) -> io::Result<()> {
+        (self.0)(event);
+        Ok(())
     }
 }
 
-// PRIVATES
-
 /// Simple helper for housing a tarball in a buffer. We just want the bytes
 /// and this keeps us from writing to disk.
 struct Tarchive(Vec<u8>);
@@ -277,3 +342,208 @@ impl Write for Tarchive {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::Filters;
+    use crate::image::build_image_handler::BuildImageHandler;
+    use crate::image::progress::ProgressEvent;
+    use crate::image::prune::{BuildCachePruneReport, ImageDeleteResponseItem, ImagePruneReport};
+    use crate::image::Events;
+    use crate::output::Discard;
+    use crate::output::ResponseHandler;
+    use crate::testing::{MockResponse, MockTransport};
+    use proptest::prelude::*;
+
+    /// Lines shaped like the daemon's progress stream, with values of the
+    /// wrong type or missing altogether.
+    fn progress_line() -> impl Strategy<Value = String> {
+        "\\{\"(aux|error|message|stream)\":(null|[0-9]{1,3}|\"[^\"\\\\]{0,16}\"|\\{\"ID\":(null|[0-9]|\"[^\"\\\\]{0,16}\")\\})\\}\n"
+    }
+
+    proptest! {
+        #[test]
+        fn build_handler_accepts_malformed_progress(lines in prop::collection::vec(progress_line(), 0..8)) {
+            let mut build = BuildImageHandler::new(Discard);
+            for line in &lines {
+                prop_assert!(build.write(line.as_bytes()).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn build_handler_keeps_the_digest_hex() {
+        let mut handler = BuildImageHandler::new(Discard);
+        handler
+            .write(b"{\"aux\":{\"ID\":\"sha256:e821df6f41ad\"}}\n{\"aux\":{\"ID\":\"abc\"}}\n")
+            .unwrap();
+        assert_eq!(handler.image_id.as_deref(), Some("abc"));
+        handler
+            .write(b"{\"aux\":{\"ID\":\"sha256:e821df6f41ad\"}}\n")
+            .unwrap();
+        assert_eq!(handler.image_id.as_deref(), Some("e821df6f41ad"));
+    }
+
+    #[test]
+    fn build_handler_reads_lines_split_at_every_offset() {
+        let stream = "{\"stream\":\"Step 1/1 : FROM alpine \u{2713}\\n\"}\r\n\
+                      {\"aux\":{\"ID\":\"sha256:e821df6f41ad\"}}\r\n\
+                      {\"error\":\"The command returned a non-zero code: 1\"}\r\n";
+        let bytes = stream.as_bytes();
+        for split in 0..=bytes.len() {
+            let mut handler = BuildImageHandler::new(Discard);
+            handler.write(&bytes[..split]).unwrap();
+            handler.write(&bytes[split..]).unwrap();
+            handler.finish();
+            assert_eq!(handler.image_id.as_deref(), Some("e821df6f41ad"));
+            assert_eq!(
+                handler.error_message.as_deref(),
+                Some("The command returned a non-zero code: 1")
+            );
+        }
+    }
+
+    #[test]
+    fn build_handler_forwards_events() {
+        let mut events = vec![];
+        let mut handler =
+            BuildImageHandler::new(Events(|event: &ProgressEvent| events.push(event.clone())));
+        handler
+            .write(b"{\"status\":\"Pulling fs layer\",\"progressDetail\":{},\"id\":\"df20fa9351a1\"}\r\n{\"stream\":\"done\"}")
+            .unwrap();
+        handler.finish();
+        drop(handler);
+
+        assert_eq!(
+            events,
+            vec![
+                ProgressEvent::Status {
+                    id: Some("df20fa9351a1".to_string()),
+                    status: "Pulling fs layer".to_string(),
+                    progress_detail: Some(Default::default()),
+                },
+                ProgressEvent::Stream("done".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn prunes_with_filters() {
+        let transport = MockTransport::new();
+        transport
+            .route(
+                "POST",
+                "/images/prune",
+                MockResponse::json(200, r#"{"ImagesDeleted":null,"SpaceReclaimed":0}"#),
+            )
+            .route(
+                "POST",
+                "/build/prune",
+                MockResponse::json(200, r#"{"CachesDeleted":null,"SpaceReclaimed":0}"#),
+            );
+        let client = transport.client();
+
+        let report = client
+            .delete_unused_images(&Filters::new().dangling(false), Discard)
+            .unwrap();
+        assert_eq!(report, ImagePruneReport::default());
+        client
+            .delete_unused_images(&Filters::new(), Discard)
+            .unwrap();
+        let report = client
+            .delete_builder_cache(0, true, &Filters::new().until("24h"), Discard)
+            .unwrap();
+        assert_eq!(report, BuildCachePruneReport::default());
+
+        let requests = transport.requests();
+        assert_eq!(
+            requests[0].query.as_deref(),
+            Some("filters=%7B%22dangling%22%3A%5B%22false%22%5D%7D")
+        );
+        assert_eq!(requests[1].query, None);
+        assert_eq!(
+            requests[2].query.as_deref(),
+            Some("keep-storage=0&all=true&filters=%7B%22until%22%3A%5B%2224h%22%5D%7D")
+        );
+    }
+
+    #[test]
+    fn reports_what_prunes_reclaimed() {
+        let transport = MockTransport::new();
+        transport
+            .route(
+                "POST",
+                "/images/prune",
+                MockResponse::json(
+                    200,
+                    r#"{"ImagesDeleted":[{"Untagged":"tfb/app:latest"},{"Deleted":"sha256:e821df6f41ad"}],"SpaceReclaimed":52428800}"#,
+                ),
+            )
+            .route(
+                "POST",
+                "/build/prune",
+                MockResponse::json(
+                    200,
+                    r#"{"CachesDeleted":["i4vlhl6sbpzb","tkuqlfgfjnom"],"SpaceReclaimed":1048576}"#,
+                ),
+            );
+        let client = transport.client();
+
+        let report = client
+            .delete_unused_images(&Filters::new(), Discard)
+            .unwrap();
+        assert_eq!(
+            report,
+            ImagePruneReport {
+                deleted: vec![
+                    ImageDeleteResponseItem {
+                        untagged: Some("tfb/app:latest".to_string()),
+                        deleted: None,
+                    },
+                    ImageDeleteResponseItem {
+                        untagged: None,
+                        deleted: Some("sha256:e821df6f41ad".to_string()),
+                    },
+                ],
+                space_reclaimed: 52_428_800,
+            }
+        );
+
+        let report = client
+            .delete_builder_cache(0, false, &Filters::new(), Discard)
+            .unwrap();
+        assert_eq!(report.deleted, vec!["i4vlhl6sbpzb", "tkuqlfgfjnom"]);
+        assert_eq!(report.space_reclaimed, 1_048_576);
+    }
+
+    #[test]
+    fn reports_what_deleting_an_image_removed() {
+        let transport = MockTransport::new();
+        transport.route(
+            "DELETE",
+            "/images/*",
+            MockResponse::json(
+                200,
+                r#"[{"Untagged":"tfb/app:latest"},{"Deleted":"sha256:e821df6f41ad"}]"#,
+            ),
+        );
+
+        let deleted = transport
+            .client()
+            .delete_image("tfb/app:latest", false, false, Discard)
+            .unwrap();
+        assert_eq!(
+            deleted,
+            vec![
+                ImageDeleteResponseItem {
+                    untagged: Some("tfb/app:latest".to_string()),
+                    deleted: None,
+                },
+                ImageDeleteResponseItem {
+                    untagged: None,
+                    deleted: Some("sha256:e821df6f41ad".to_string()),
+                },
+            ]
+        );
+    }
+}
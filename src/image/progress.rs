@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+/// A line of the JSON progress stream sent while building or pulling an
+/// image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    /// Build output, such as `Step 1/4 : FROM alpine\n`.
+    Stream(String),
+    /// The state of a pull, or of one of its layers when `id` is set.
+    Status {
+        id: Option<String>,
+        status: String,
+        progress_detail: Option<ProgressDetail>,
+    },
+    /// Structured build output; carries the id of the image built.
+    Aux(BuildAux),
+    /// The build or pull failed.
+    Error {
+        error: String,
+        error_detail: Option<ErrorDetail>,
+    },
+}
+impl ProgressEvent {
+    /// Parses a single line of the stream, returning `None` for lines which
+    /// are not JSON or carry none of the known keys.
+    pub fn parse(line: &str) -> Option<Self> {
+        let message: Message = serde_json::from_str(line).ok()?;
+
+        if let Some(error) = message.error {
+            Some(ProgressEvent::Error {
+                error,
+                error_detail: message.error_detail,
+            })
+        } else if let Some(error) = message.message {
+            // Older daemons report failures as a bare message.
+            Some(ProgressEvent::Error {
+                error,
+                error_detail: None,
+            })
+        } else if let Some(stream) = message.stream {
+            Some(ProgressEvent::Stream(stream))
+        } else if let Some(status) = message.status {
+            Some(ProgressEvent::Status {
+                id: message.id,
+                status,
+                progress_detail: message.progress_detail,
+            })
+        } else {
+            message
+                .aux
+                .and_then(|aux| serde_json::from_value(aux).ok())
+                .map(ProgressEvent::Aux)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressDetail {
+    /// Bytes transferred so far.
+    pub current: Option<u64>,
+    /// Size of the layer, when known.
+    pub total: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BuildAux {
+    /// The image digest, such as `sha256:e821df6f...`.
+    #[serde(rename = "ID")]
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ErrorDetail {
+    pub code: Option<i64>,
+    pub message: Option<String>,
+}
+
+/// Every key a line of the stream may carry.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Message {
+    stream: Option<String>,
+    status: Option<String>,
+    id: Option<String>,
+    progress_detail: Option<ProgressDetail>,
+    aux: Option<serde_json::Value>,
+    error: Option<String>,
+    error_detail: Option<ErrorDetail>,
+    message: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::progress::{BuildAux, ErrorDetail, ProgressDetail, ProgressEvent};
+
+    #[test]
+    fn parses_build_and_pull_lines() {
+        assert_eq!(
+            ProgressEvent::parse(r#"{"stream":"Step 1/2 : FROM alpine\n"}"#),
+            Some(ProgressEvent::Stream(
+                "Step 1/2 : FROM alpine\n".to_string()
+            ))
+        );
+        assert_eq!(
+            ProgressEvent::parse(
+                r#"{"status":"Downloading","progressDetail":{"current":1024,"total":2811478},"progress":"[>   ]","id":"df20fa9351a1"}"#
+            ),
+            Some(ProgressEvent::Status {
+                id: Some("df20fa9351a1".to_string()),
+                status: "Downloading".to_string(),
+                progress_detail: Some(ProgressDetail {
+                    current: Some(1024),
+                    total: Some(2811478),
+                }),
+            })
+        );
+        assert_eq!(
+            ProgressEvent::parse(r#"{"aux":{"ID":"sha256:e821df6f41ad"}}"#),
+            Some(ProgressEvent::Aux(BuildAux {
+                id: "sha256:e821df6f41ad".to_string()
+            }))
+        );
+        assert_eq!(
+            ProgressEvent::parse(
+                r#"{"errorDetail":{"code":1,"message":"exit code: 1"},"error":"exit code: 1"}"#
+            ),
+            Some(ProgressEvent::Error {
+                error: "exit code: 1".to_string(),
+                error_detail: Some(ErrorDetail {
+                    code: Some(1),
+                    message: Some("exit code: 1".to_string()),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn skips_unknown_lines() {
+        assert_eq!(ProgressEvent::parse(""), None);
+        assert_eq!(ProgressEvent::parse("not json"), None);
+        assert_eq!(
+            ProgressEvent::parse(r#"{"aux":{"Digest":"sha256:00"}}"#),
+            None
+        );
+        assert_eq!(ProgressEvent::parse(r#"{"stream":5}"#), None);
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Deserializer};
+
+/// What `delete_unused_images` removed.
+///
+/// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/ImagePrune)
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImagePruneReport {
+    #[serde(rename = "ImagesDeleted", default, deserialize_with = "null_as_empty")]
+    pub deleted: Vec<ImageDeleteResponseItem>,
+    /// Disk space freed, in bytes.
+    #[serde(rename = "SpaceReclaimed", default)]
+    pub space_reclaimed: u64,
+}
+
+/// An image tag removed or an image deleted by a prune or `delete_image`.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageDeleteResponseItem {
+    /// The reference which was untagged, such as `tfb/app:latest`.
+    #[serde(rename = "Untagged", default)]
+    pub untagged: Option<String>,
+    /// The id of the image which was deleted.
+    #[serde(rename = "Deleted", default)]
+    pub deleted: Option<String>,
+}
+
+/// What `delete_builder_cache` removed.
+///
+/// [Reference](https://docs.docker.com/engine/api/v1.41/#operation/BuildPrune)
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildCachePruneReport {
+    /// The ids of the cache records deleted.
+    #[serde(rename = "CachesDeleted", default, deserialize_with = "null_as_empty")]
+    pub deleted: Vec<String>,
+    /// Disk space freed, in bytes.
+    #[serde(rename = "SpaceReclaimed", default)]
+    pub space_reclaimed: u64,
+}
+
+/// Reads a list the daemon sends as `null` when there is nothing in it.
+fn null_as_empty<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<Vec<T>, D::Error> {
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
@@ -1,6 +1,16 @@
+pub mod client;
 pub mod container;
 pub mod error;
+pub mod filters;
 pub mod image;
 pub mod network;
+pub mod output;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+pub use crate::client::DockerClient;
+
+#[cfg(not(any(unix, feature = "curl")))]
+compile_error!("dockurl needs the `curl` feature on platforms without Unix sockets");
 
 extern crate strum;
@@ -1,42 +1,50 @@
-use curl::easy::{Handler, WriteError};
+use crate::client::line_decoder::LineDecoder;
+use crate::output::{OutputSink, ResponseHandler};
 use serde_json::Value;
+use std::io;
 
-pub struct CreateNetworkHandler<H: Handler> {
+pub struct CreateNetworkHandler<S: OutputSink> {
     pub network_id: Option<String>,
-    pub error_message: Option<String>,
-    log_handler: H,
+    lines: LineDecoder,
+    log_handler: S,
 }
-impl<H: Handler> CreateNetworkHandler<H> {
-    pub fn new(log_handler: H) -> Self {
+impl<S: OutputSink> CreateNetworkHandler<S> {
+    pub fn new(log_handler: S) -> Self {
         Self {
             network_id: None,
-            error_message: None,
+            lines: LineDecoder::new(),
             log_handler,
         }
     }
-}
-impl<H: Handler> Handler for CreateNetworkHandler<H> {
-    fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
-        self.log_handler.write(data)?;
 
-        if let Ok(logs) = std::str::from_utf8(&data) {
-            for line in logs.lines() {
-                if !line.trim().is_empty() {
-                    if let Ok(json) = serde_json::from_str::<Value>(line) {
-                        if !json["Id"].is_null() {
-                            let mut network_id = json["Id"].as_str().unwrap();
-                            network_id = &network_id[0..12];
-                            self.network_id = Some(network_id.to_string());
-                        } else if !json["message"].is_null() {
-                            // fixme - this APPEARS to be how docker communicates error messages.
-                            let error = json["message"].as_str().unwrap().to_string();
-                            self.error_message = Some(error);
-                        }
-                    }
-                }
+    /// Reads a response which did not end with a newline.
+    pub fn finish(&mut self) {
+        if let Some(line) = self.lines.finish() {
+            self.line(&line);
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        if let Ok(json) = serde_json::from_str::<Value>(line) {
+            if let Some(id) = json["Id"].as_str() {
+                self.network_id = Some(id.get(..12).unwrap_or(id).to_string());
             }
         }
+    }
+}
+impl<S: OutputSink> ResponseHandler for CreateNetworkHandler<S> {
+    fn header(&mut self, line: &[u8]) -> io::Result<()> {
+        self.log_handler.on_header(line)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.log_handler.on_stdout(data)?;
+
+        self.lines.push(data);
+        while let Some(line) = self.lines.next_line() {
+            self.line(&line);
+        }
 
-        Ok(data.len())
+        Ok(())
     }
 }
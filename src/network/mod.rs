@@ -1,19 +1,11 @@
-mod connect_container_to_network_handler;
 mod create_network_handler;
-mod inspect_network_handler;
-
-use crate::error::DockerError::{
-    DockerNetworkAlreadyExistsCreateError, DockerNetworkCreateError, DockerNetworkDeleteError,
-    DockerServerError, FailedToAttachDockerContainerToNetworkError,
-    FailedToCreateDockerNetworkError, FailedToDeleteNetworkError, InspectNetworkError,
-    NetworkNotFoundError, NetworkOrContainerNotFoundError, OperationNotSupportedError,
-    UnknownDockerError,
-};
-use crate::error::DockerResult;
-use crate::network::connect_container_to_network_handler::ConnectContainerToNetworkHandler;
+
+use crate::client::request::Request;
+use crate::client::url::Url;
+use crate::client::DockerClient;
+use crate::error::{DockerResult, Operation};
 use crate::network::create_network_handler::CreateNetworkHandler;
-use crate::network::inspect_network_handler::InspectNetworkHandler;
-use curl::easy::{Easy2, Handler, List};
+use crate::output::{AccumulatingHandler, OutputHandler, OutputSink};
 use serde::{Deserialize, Serialize};
 use std::string::ToString;
 use strum_macros::Display;
@@ -34,62 +26,6 @@ struct NetworkCreationOptions {
     pub check_duplicate: bool,
 }
 
-///
-/// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkCreate)
-pub fn create_network<H: Handler>(
-    network_name: &str,
-    network_mode: NetworkMode,
-    docker_host: &str,
-    use_unix_socket: bool,
-    log_handler: H,
-) -> DockerResult<String> {
-    let mut easy = Easy2::new(CreateNetworkHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    let mut headers = List::new();
-    headers.append("Content-Type: application/json")?;
-
-    let options = NetworkCreationOptions {
-        name: network_name.to_string(),
-        driver: network_mode.to_string(),
-        internal: false,
-        check_duplicate: true,
-    };
-    let json = serde_json::to_string(&options)?;
-    let len = json.len();
-
-    easy.post(true)?;
-    easy.url(&format!("http://{}/networks/create", docker_host))?;
-    easy.http_headers(headers)?;
-    easy.in_filesize(len as u64)?;
-    easy.post_field_size(len as u64)?;
-    easy.post_fields_copy(json.as_bytes())?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(201) => {
-            if let Some(network_id) = &easy.get_ref().network_id {
-                return Ok(network_id.clone());
-            } else {
-                let error_message = &easy.get_ref().error_message;
-                if error_message.is_some() {
-                    return Err(FailedToCreateDockerNetworkError(
-                        error_message.clone().unwrap(),
-                    ));
-                }
-            }
-            Err(DockerNetworkCreateError)
-        }
-        Ok(409) => Err(DockerNetworkAlreadyExistsCreateError(
-            network_name.to_string(),
-        )),
-        Ok(_code) => Err(DockerNetworkCreateError),
-        Err(e) => Err(FailedToCreateDockerNetworkError(e.to_string())),
-    }
-}
-
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 struct NetworkConnectOptions {
@@ -109,114 +45,161 @@ struct IPAMConfig {
     pub aliases: Vec<String>,
 }
 
+impl DockerClient {
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkCreate)
+    pub fn create_network<S: OutputSink>(
+        &self,
+        network_name: &str,
+        network_mode: NetworkMode,
+        log_handler: S,
+    ) -> DockerResult<String> {
+        let options = NetworkCreationOptions {
+            name: network_name.to_string(),
+            driver: network_mode.to_string(),
+            internal: false,
+            check_duplicate: true,
+        };
+        let json = serde_json::to_string(&options)?;
+
+        let mut handler = CreateNetworkHandler::new(log_handler);
+        let request = Request::post(Operation::NetworkCreate, "/networks/create")
+            .resource(network_name)
+            .json(json);
+
+        let response = self.execute(&request, &mut handler)?;
+        handler.finish();
+        match response.status {
+            201 => match handler.network_id {
+                Some(network_id) => Ok(network_id),
+                None => Err(request.error_message(201, "the response had no network id")),
+            },
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkConnect)
+    pub fn connect_container_to_network<S: OutputSink>(
+        &self,
+        container_id: &str,
+        network_id: &str,
+        aliases: Vec<String>,
+        log_handler: S,
+    ) -> DockerResult<()> {
+        let options = NetworkConnectOptions {
+            container: container_id.to_string(),
+            endpoint_config: EndpointConfig {
+                i_p_a_m_config: IPAMConfig { aliases },
+            },
+        };
+        let json = serde_json::to_string(&options)?;
+
+        let url = Url::new("/networks").segment(network_id).segment("connect");
+        let request = Request::post(Operation::NetworkConnect, url)
+            .resource(network_id)
+            .json(json);
+
+        let mut handler = OutputHandler::new(log_handler);
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(()),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkDelete)
+    pub fn delete_network<S: OutputSink>(
+        &self,
+        network_name: &str,
+        log_handler: S,
+    ) -> DockerResult<()> {
+        let url = Url::new("/networks").segment(network_name);
+        let request = Request::delete(Operation::NetworkDelete, url).resource(network_name);
+
+        let mut handler = OutputHandler::new(log_handler);
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            204 => Ok(()),
+            _ => Err(request.error(&response)),
+        }
+    }
+
+    ///
+    /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkInspect)
+    pub fn inspect_network<S: OutputSink>(
+        &self,
+        network_id_or_name: &str,
+        log_handler: S,
+    ) -> DockerResult<Network> {
+        let mut handler = AccumulatingHandler::new(log_handler);
+        let url = Url::new("/networks").segment(network_id_or_name);
+        let request = Request::get(Operation::NetworkInspect, url).resource(network_id_or_name);
+
+        let response = self.execute(&request, &mut handler)?;
+        match response.status {
+            200 => Ok(serde_json::from_slice(&handler.accumulator)?),
+            _ => Err(request.error(&response)),
+        }
+    }
+}
+
+///
+/// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkCreate)
+pub fn create_network<S: OutputSink>(
+    network_name: &str,
+    network_mode: NetworkMode,
+    docker_host: &str,
+    use_unix_socket: bool,
+    log_handler: S,
+) -> DockerResult<String> {
+    DockerClient::new(docker_host, use_unix_socket).create_network(
+        network_name,
+        network_mode,
+        log_handler,
+    )
+}
+
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkConnect)
-pub fn connect_container_to_network<H: Handler>(
+pub fn connect_container_to_network<S: OutputSink>(
     container_id: &str,
     network_id: &str,
     aliases: Vec<String>,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<()> {
-    let mut easy = Easy2::new(ConnectContainerToNetworkHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    let mut headers = List::new();
-    headers.append("Content-Type: application/json")?;
-
-    let options = NetworkConnectOptions {
-        container: container_id.to_string(),
-        endpoint_config: EndpointConfig {
-            i_p_a_m_config: IPAMConfig { aliases },
-        },
-    };
-    let json = serde_json::to_string(&options)?;
-    let len = json.len();
-
-    easy.post(true)?;
-    easy.url(&format!(
-        "http://{}/networks/{}/connect",
-        docker_host, network_id
-    ))?;
-    easy.http_headers(headers)?;
-    easy.in_filesize(len as u64)?;
-    easy.post_field_size(len as u64)?;
-    easy.post_fields_copy(json.as_bytes())?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(200) => Ok(()),
-        Ok(403) => Err(OperationNotSupportedError),
-        Ok(404) => Err(NetworkOrContainerNotFoundError(
-            network_id.to_string(),
-            container_id.to_string(),
-        )),
-        Ok(500) => Err(DockerServerError),
-        Ok(code) => Err(UnknownDockerError(format!(
-            "Response code: {}; Response: {}",
-            code,
-            easy.get_ref().body()
-        ))),
-        Err(e) => Err(FailedToAttachDockerContainerToNetworkError(e.to_string())),
-    }
+    DockerClient::new(docker_host, use_unix_socket).connect_container_to_network(
+        container_id,
+        network_id,
+        aliases,
+        log_handler,
+    )
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkDelete)
-pub fn delete_network<H: Handler>(
+pub fn delete_network<S: OutputSink>(
     network_name: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<()> {
-    let mut easy = Easy2::new(log_handler);
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.custom_request("DELETE")?;
-    easy.url(&format!("http://{}/networks/{}", docker_host, network_name))?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(204) => Ok(()),
-        Ok(_) => Err(DockerNetworkDeleteError),
-        Err(e) => Err(FailedToDeleteNetworkError(e.to_string())),
-    }
+    DockerClient::new(docker_host, use_unix_socket).delete_network(network_name, log_handler)
 }
 
 ///
 /// [Reference](https://docs.docker.com/engine/api/v1.40/#operation/NetworkInspect)
-pub fn inspect_network<H: Handler>(
+pub fn inspect_network<S: OutputSink>(
     network_id_or_name: &str,
     docker_host: &str,
     use_unix_socket: bool,
-    log_handler: H,
+    log_handler: S,
 ) -> DockerResult<Network> {
-    let mut easy = Easy2::new(InspectNetworkHandler::new(log_handler));
-    if use_unix_socket {
-        easy.unix_socket("/var/run/docker.sock")?;
-    }
-
-    easy.url(&format!(
-        "http://{}/networks/{}",
-        docker_host, network_id_or_name
-    ))?;
-    easy.perform()?;
-
-    match easy.response_code() {
-        Ok(200) => {
-            let network: Network = serde_json::from_str(&easy.get_ref().body()).unwrap();
-            Ok(network)
-        }
-        Ok(404) => Err(NetworkNotFoundError(network_id_or_name.to_string())),
-        _ => Err(InspectNetworkError),
-    }
+    DockerClient::new(docker_host, use_unix_socket).inspect_network(network_id_or_name, log_handler)
 }
-
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct Network {
@@ -224,3 +207,39 @@ pub struct Network {
     pub id: String,
     // todo
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::network::create_network_handler::CreateNetworkHandler;
+    use crate::output::Discard;
+    use crate::output::ResponseHandler;
+    use proptest::prelude::*;
+
+    fn response_line() -> impl Strategy<Value = String> {
+        "\\{\"(Id|message)\":(null|[0-9]{1,3}|\"\\PC{0,20}\")\\}\n"
+    }
+
+    proptest! {
+        #[test]
+        fn handler_accepts_malformed_responses(lines in prop::collection::vec(response_line(), 0..8)) {
+            let mut create = CreateNetworkHandler::new(Discard);
+            for line in &lines {
+                prop_assert!(create.write(line.as_bytes()).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn create_handler_reads_responses_split_at_every_offset() {
+        let response = "{\"Id\":\"22be93d5babb089c5aab8dbc369042fad48ff791\",\
+                        \"Warning\":\"r\u{e9}seau\"}\r\n";
+        let bytes = response.as_bytes();
+        for split in 0..=bytes.len() {
+            let mut handler = CreateNetworkHandler::new(Discard);
+            handler.write(&bytes[..split]).unwrap();
+            handler.write(&bytes[split..]).unwrap();
+            handler.finish();
+            assert_eq!(handler.network_id.as_deref(), Some("22be93d5babb"));
+        }
+    }
+}
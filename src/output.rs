@@ -0,0 +1,392 @@
+//! Where the output of a call to the daemon goes: what a container writes,
+//! the progress of builds and pulls, and the bodies of other responses.
+
+use crate::client::line_decoder::LineDecoder;
+use crate::image::progress::ProgressEvent;
+#[cfg(feature = "curl")]
+use curl::easy::Handler;
+use std::io::{self, Write};
+
+/// Receives the output of a call to the daemon.
+///
+/// Every method has a default, so a sink implements only what it wants.
+/// Returning an error from any of them aborts the call.
+///
+/// With the `curl` feature, anything implementing curl's `Handler` is a sink
+/// as well. It receives the response headers through `Handler::header` and
+/// both streams through `Handler::write`, progress included, as the daemon
+/// sent them.
+pub trait OutputSink {
+    /// A header line of the response, terminator included.
+    fn on_header(&mut self, _line: &[u8]) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// What a container wrote to stdout, or the body of a response which is
+    /// neither container output nor progress.
+    fn on_stdout(&mut self, _data: &[u8]) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// What a container wrote to stderr.
+    fn on_stderr(&mut self, _data: &[u8]) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// An event of the progress stream of a build or pull. Its text is
+    /// passed on to `on_stdout` by default, and failures to `on_stderr`.
+    fn on_progress(&mut self, event: &ProgressEvent) -> io::Result<()> {
+        match event {
+            ProgressEvent::Stream(text) => self.on_stdout(text.as_bytes()),
+            ProgressEvent::Status {
+                id: Some(id),
+                status,
+                ..
+            } => self.on_stdout(format!("{}: {}\n", id, status).as_bytes()),
+            ProgressEvent::Status { status, .. } => {
+                self.on_stdout(format!("{}\n", status).as_bytes())
+            }
+            ProgressEvent::Aux(_) => Ok(()),
+            ProgressEvent::Error { error, .. } => self.on_stderr(format!("{}\n", error).as_bytes()),
+        }
+    }
+
+    /// Whether the progress stream of a build or pull is passed to
+    /// `on_stdout` as the daemon sent it, a JSON object per line, instead of
+    /// being parsed and passed to `on_progress`.
+    fn raw_progress(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "curl")]
+impl<H: Handler> OutputSink for H {
+    fn on_header(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.header(line) {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "the handler refused a header",
+            ))
+        }
+    }
+
+    fn on_stdout(&mut self, data: &[u8]) -> io::Result<()> {
+        match self.write(data) {
+            Ok(written) if written == data.len() => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "the handler did not take all of its output",
+            )),
+        }
+    }
+
+    fn on_stderr(&mut self, data: &[u8]) -> io::Result<()> {
+        self.on_stdout(data)
+    }
+
+    fn raw_progress(&self) -> bool {
+        true
+    }
+}
+
+/// Throws all output away.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Discard;
+impl OutputSink for Discard {}
+
+/// Keeps all output in memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Collect {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+impl Collect {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl OutputSink for Collect {
+    fn on_stdout(&mut self, data: &[u8]) -> io::Result<()> {
+        self.stdout.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn on_stderr(&mut self, data: &[u8]) -> io::Result<()> {
+        self.stderr.extend_from_slice(data);
+        Ok(())
+    }
+}
+
+/// Writes both streams to a `std::io::Write`, such as `std::io::stdout()`
+/// or a file.
+#[derive(Debug, Clone, Default)]
+pub struct WriteSink<W: Write> {
+    writer: W,
+}
+impl<W: Write> WriteSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+impl<W: Write> OutputSink for WriteSink<W> {
+    fn on_stdout(&mut self, data: &[u8]) -> io::Result<()> {
+        self.writer.write_all(data)
+    }
+
+    fn on_stderr(&mut self, data: &[u8]) -> io::Result<()> {
+        self.writer.write_all(data)
+    }
+}
+
+/// Logs output a line at a time through the `log` crate: stdout at `info`
+/// and stderr at `warn`, under the `dockurl` target. A last line without a
+/// newline is logged when the sink is dropped.
+#[derive(Debug, Default)]
+pub struct LogSink {
+    stdout: LineDecoder,
+    stderr: LineDecoder,
+}
+impl LogSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+impl OutputSink for LogSink {
+    fn on_stdout(&mut self, data: &[u8]) -> io::Result<()> {
+        self.stdout.push(data);
+        while let Some(line) = self.stdout.next_line() {
+            log::info!(target: "dockurl", "{}", line);
+        }
+        Ok(())
+    }
+
+    fn on_stderr(&mut self, data: &[u8]) -> io::Result<()> {
+        self.stderr.push(data);
+        while let Some(line) = self.stderr.next_line() {
+            log::warn!(target: "dockurl", "{}", line);
+        }
+        Ok(())
+    }
+}
+impl Drop for LogSink {
+    fn drop(&mut self) {
+        if let Some(line) = self.stdout.finish() {
+            log::info!(target: "dockurl", "{}", line);
+        }
+        if let Some(line) = self.stderr.finish() {
+            log::warn!(target: "dockurl", "{}", line);
+        }
+    }
+}
+
+/// Receives the response to a call, making what it needs of it and passing
+/// the rest on to the call's sink. Returning an error from either method
+/// aborts the transfer.
+pub(crate) trait ResponseHandler {
+    /// A header line, terminator included.
+    fn header(&mut self, _line: &[u8]) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// The next piece of the body.
+    fn write(&mut self, data: &[u8]) -> io::Result<()>;
+}
+
+/// Passes a response body on to the stdout of a sink, for calls whose body
+/// needs no further handling.
+pub(crate) struct OutputHandler<S: OutputSink> {
+    pub sink: S,
+}
+impl<S: OutputSink> OutputHandler<S> {
+    pub fn new(sink: S) -> Self {
+        Self { sink }
+    }
+}
+impl<S: OutputSink> ResponseHandler for OutputHandler<S> {
+    fn header(&mut self, line: &[u8]) -> io::Result<()> {
+        self.sink.on_header(line)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.sink.on_stdout(data)
+    }
+}
+
+/// Keeps the body of a response while passing it on, for calls whose result
+/// is read from the whole body.
+pub(crate) struct AccumulatingHandler<S: OutputSink> {
+    pub accumulator: Vec<u8>,
+    sink: S,
+}
+impl<S: OutputSink> AccumulatingHandler<S> {
+    pub fn new(sink: S) -> Self {
+        Self {
+            accumulator: vec![],
+            sink,
+        }
+    }
+}
+impl<S: OutputSink> ResponseHandler for AccumulatingHandler<S> {
+    fn header(&mut self, line: &[u8]) -> io::Result<()> {
+        self.sink.on_header(line)
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.sink.on_stdout(data)?;
+        self.accumulator.extend_from_slice(data);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::progress::ProgressEvent;
+    use crate::output::{
+        AccumulatingHandler, Collect, OutputHandler, OutputSink, ResponseHandler, WriteSink,
+    };
+    use crate::testing::{MockResponse, MockTransport};
+    #[cfg(feature = "curl")]
+    use curl::easy::{Handler, WriteError};
+    use proptest::prelude::*;
+    #[cfg(feature = "curl")]
+    use std::cell::RefCell;
+    use std::io;
+    #[cfg(feature = "curl")]
+    use std::rc::Rc;
+
+    /// A handler written before sinks existed, whose clones share what they
+    /// receive.
+    #[cfg(feature = "curl")]
+    #[derive(Clone, Default)]
+    struct Legacy {
+        headers: Rc<RefCell<Vec<String>>>,
+        body: Rc<RefCell<Vec<u8>>>,
+    }
+    #[cfg(feature = "curl")]
+    impl Handler for Legacy {
+        fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
+            self.body.borrow_mut().extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn header(&mut self, data: &[u8]) -> bool {
+            self.headers
+                .borrow_mut()
+                .push(String::from_utf8_lossy(data).into_owned());
+            true
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn handlers_pass_every_byte_through(chunks in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..256), 0..8)) {
+            let mut output = OutputHandler::new(Collect::new());
+            let mut accumulating = AccumulatingHandler::new(Collect::new());
+            for chunk in &chunks {
+                output.write(chunk).unwrap();
+                accumulating.write(chunk).unwrap();
+            }
+            let body = chunks.concat();
+            prop_assert_eq!(&output.sink.stdout, &body);
+            prop_assert_eq!(&accumulating.sink.stdout, &body);
+            prop_assert_eq!(&accumulating.accumulator, &body);
+        }
+    }
+
+    #[test]
+    fn progress_is_written_as_text() {
+        let mut sink = Collect::new();
+        for line in &[
+            r#"{"stream":"Step 1/2 : FROM alpine\n"}"#,
+            r#"{"status":"Downloading","id":"a1b2","progressDetail":{"current":1}}"#,
+            r#"{"aux":{"ID":"sha256:e821df6f41ad"}}"#,
+            r#"{"error":"manifest unknown"}"#,
+        ] {
+            sink.on_progress(&ProgressEvent::parse(line).unwrap())
+                .unwrap();
+        }
+        assert_eq!(sink.stdout, b"Step 1/2 : FROM alpine\na1b2: Downloading\n");
+        assert_eq!(sink.stderr, b"manifest unknown\n");
+    }
+
+    #[test]
+    #[cfg(feature = "curl")]
+    fn handlers_are_sinks() {
+        let mut legacy = Legacy::default();
+        legacy.on_stdout(b"out ").unwrap();
+        legacy.on_stderr(b"err").unwrap();
+        assert_eq!(*legacy.body.borrow(), b"out err");
+    }
+
+    #[test]
+    #[cfg(feature = "curl")]
+    fn handlers_get_progress_and_headers_as_sent() {
+        let progress = concat!(
+            r#"{"status":"Pulling from library/alpine","id":"latest"}"#,
+            "\n",
+            r#"{"aux":{"ID":"sha256:e821df6f41ad"},"unknown":true}"#,
+            "\n",
+        );
+        let transport = MockTransport::new();
+        transport.route(
+            "POST",
+            "/images/create",
+            MockResponse::stream(200, &[&progress[..20], &progress[20..]]),
+        );
+
+        let legacy = Legacy::default();
+        transport
+            .client()
+            .create_image("alpine", "latest", legacy.clone())
+            .unwrap();
+        assert_eq!(*legacy.body.borrow(), progress.as_bytes());
+        let headers = legacy.headers.borrow();
+        assert_eq!(headers[0], "HTTP/1.1 200 OK\r\n");
+        assert!(headers.contains(&"Content-Type: application/json\r\n".to_string()));
+    }
+
+    #[test]
+    fn writers_are_sinks() {
+        let mut sink = WriteSink::new(Vec::new());
+        sink.on_stdout(b"out ").unwrap();
+        sink.on_stderr(b"err").unwrap();
+        assert_eq!(sink.into_inner(), b"out err");
+    }
+
+    #[test]
+    fn calls_return_their_sink_and_abort_when_it_fails() {
+        #[derive(Debug)]
+        struct Full;
+        impl OutputSink for Full {
+            fn on_stdout(&mut self, _data: &[u8]) -> io::Result<()> {
+                Err(io::ErrorKind::WriteZero.into())
+            }
+        }
+
+        let transport = MockTransport::new();
+        transport
+            .route(
+                "GET",
+                "/containers/*/json",
+                MockResponse::json(200, r#"{"Config":{"Tty":true}}"#),
+            )
+            .route(
+                "GET",
+                "/containers/*/logs",
+                MockResponse::stream(200, &["hello\n", "world\n"]),
+            );
+        let client = transport.client();
+
+        let logs = client.get_container_logs("abc", Collect::new()).unwrap();
+        assert_eq!(logs.stdout, b"hello\nworld\n");
+        let error = client.get_container_logs("abc", Full).unwrap_err();
+        assert!(error.to_string().contains("aborted"));
+    }
+}
@@ -0,0 +1,755 @@
+//! An in-process stand-in for the Docker daemon, for testing code which
+//! talks to it without a real daemon.
+//!
+//! A [`MockDaemon`] serves HTTP/1.1 on a temporary Unix socket, answers
+//! requests from scripted routes and records every request it receives. A
+//! [`MockTransport`] does the same in memory, without a socket or libcurl,
+//! and is the only one of the two available on platforms without Unix
+//! sockets.
+//! Available to the crate's own tests and, with the `testing` feature, to
+//! downstream crates.
+//!
+//! ```no_run
+//! use dockurl::testing::{MockDaemon, MockResponse};
+//!
+//! let daemon = MockDaemon::start().unwrap();
+//! daemon.route(
+//!     "POST",
+//!     "/containers/*/start",
+//!     MockResponse::json(404, r#"{"message":"No such container: abc"}"#),
+//! );
+//! ```
+
+use crate::client::transport::{HttpRequest, ResponseSink, Transport};
+use crate::client::version::MAX_API_VERSION;
+use crate::client::DockerClient;
+use crate::error::DockerResult;
+#[cfg(unix)]
+use std::io::{self, BufRead, BufReader, Read, Write};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+#[cfg(unix)]
+use std::thread::JoinHandle;
+
+/// A canned response for a route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    pub status: u32,
+    pub headers: Vec<(String, String)>,
+    /// The body, sent with `Transfer-Encoding: chunked` and one chunk per
+    /// element when there is more than one, as the daemon streams progress.
+    pub chunks: Vec<Vec<u8>>,
+}
+impl MockResponse {
+    /// A response with no body.
+    pub fn new(status: u32) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            chunks: vec![],
+        }
+    }
+
+    /// A response with an `application/json` body.
+    pub fn json(status: u32, body: &str) -> Self {
+        Self::new(status)
+            .header("Content-Type", "application/json")
+            .body(body.as_bytes())
+    }
+
+    /// A JSON-lines stream, such as build or pull progress, sent as one chunk
+    /// per element of `chunks`.
+    pub fn stream<S: AsRef<[u8]>>(status: u32, chunks: &[S]) -> Self {
+        let mut response = Self::new(status).header("Content-Type", "application/json");
+        response.chunks = chunks.iter().map(|c| c.as_ref().to_vec()).collect();
+        response
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: &[u8]) -> Self {
+        self.chunks = vec![body.to_vec()];
+        self
+    }
+
+    /// The status line and scripted headers, without the blank line which
+    /// ends them.
+    fn head(&self) -> Vec<String> {
+        let mut head = vec![format!(
+            "HTTP/1.1 {} {}\r\n",
+            self.status,
+            reason(self.status)
+        )];
+        for (name, value) in &self.headers {
+            head.push(format!("{}: {}\r\n", name, value));
+        }
+        head
+    }
+
+    #[cfg(unix)]
+    fn write_to(&self, stream: &mut UnixStream) -> io::Result<()> {
+        let mut head = self.head().concat();
+        if self.chunks.len() > 1 {
+            head.push_str("Transfer-Encoding: chunked\r\n\r\n");
+            stream.write_all(head.as_bytes())?;
+            for chunk in self.chunks.iter().filter(|chunk| !chunk.is_empty()) {
+                stream.write_all(format!("{:x}\r\n", chunk.len()).as_bytes())?;
+                stream.write_all(chunk)?;
+                stream.write_all(b"\r\n")?;
+                stream.flush()?;
+            }
+            stream.write_all(b"0\r\n\r\n")?;
+        } else {
+            let body = self.chunks.first().map(Vec::as_slice).unwrap_or_default();
+            head.push_str(&format!("Content-Length: {}\r\n\r\n", body.len()));
+            stream.write_all(head.as_bytes())?;
+            stream.write_all(body)?;
+        }
+        stream.flush()
+    }
+}
+
+/// A request as the mock daemon received it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// The path without its API version prefix or query string.
+    pub path: String,
+    pub query: Option<String>,
+    /// The API version the path was prefixed with, such as `1.41`.
+    pub api_version: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug)]
+struct Route {
+    method: String,
+    path: String,
+    response: MockResponse,
+    once: bool,
+}
+impl Route {
+    /// Whether `pattern` matches `path`, with `*` standing for any one
+    /// segment, such as a container id.
+    fn matches(&self, method: &str, path: &str) -> bool {
+        let pattern = self.path.trim_end_matches('/').split('/');
+        let path = path.trim_end_matches('/').split('/');
+        self.method.eq_ignore_ascii_case(method)
+            && pattern.clone().count() == path.clone().count()
+            && pattern
+                .zip(path)
+                .all(|(expected, actual)| expected == "*" || expected == actual)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    routes: Vec<Route>,
+    requests: Vec<RecordedRequest>,
+}
+impl State {
+    fn add(state: &Mutex<State>, method: &str, path: &str, response: MockResponse, once: bool) {
+        state.lock().unwrap().routes.push(Route {
+            method: method.to_string(),
+            path: path.to_string(),
+            response,
+            once,
+        });
+    }
+}
+
+/// An HTTP server on a temporary Unix socket which answers like the Docker
+/// daemon would, from routes scripted by the test.
+///
+/// Routes are tried in the order they were added and the first match
+/// answers. Unless a route says otherwise, `/_ping` is answered as a daemon
+/// speaking `MAX_API_VERSION` would, so that clients can negotiate a
+/// version, and is left out of `requests`. Other requests no route matches
+/// get a `404` with a JSON `message`, as the daemon sends for unknown paths.
+/// The server stops and its socket is removed when the `MockDaemon` is
+/// dropped.
+#[cfg(unix)]
+pub struct MockDaemon {
+    socket: PathBuf,
+    state: Arc<Mutex<State>>,
+    stopped: Arc<AtomicBool>,
+    server: Option<JoinHandle<()>>,
+}
+#[cfg(unix)]
+impl MockDaemon {
+    pub fn start() -> io::Result<Self> {
+        let socket = temp_socket("mock");
+        let listener = UnixListener::bind(&socket)?;
+
+        let state = Arc::new(Mutex::new(State::default()));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let server = {
+            let state = state.clone();
+            let stopped = stopped.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let state = state.clone();
+                        std::thread::spawn(move || serve(stream, &state));
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            socket,
+            state,
+            stopped,
+            server: Some(server),
+        })
+    }
+
+    /// Answers every `method` request to `path` with `response`. `path` is
+    /// matched without the API version prefix or query string, and `*`
+    /// matches any one segment.
+    pub fn route(&self, method: &str, path: &str, response: MockResponse) -> &Self {
+        State::add(&self.state, method, path, response, false);
+        self
+    }
+
+    /// Answers the next `method` request to `path` with `response`, after
+    /// which the route is removed. Adding several scripts a sequence.
+    pub fn route_once(&self, method: &str, path: &str, response: MockResponse) -> &Self {
+        State::add(&self.state, method, path, response, true);
+        self
+    }
+
+    /// The requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// A client which talks to this daemon.
+    pub fn client(&self) -> DockerClient {
+        DockerClient::new("localhost", false).with_unix_socket(&self.socket)
+    }
+}
+#[cfg(unix)]
+impl Drop for MockDaemon {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag.
+        let _ = UnixStream::connect(&self.socket);
+        if let Some(server) = self.server.take() {
+            let _ = server.join();
+        }
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+/// A socket which accepts connections but never answers them, for testing
+/// how calls time out or are cancelled while the daemon is wedged.
+/// Connections are held open until the `SilentDaemon` is dropped, which
+/// also removes its socket.
+#[cfg(unix)]
+pub struct SilentDaemon {
+    socket: PathBuf,
+    stopped: Arc<AtomicBool>,
+    server: Option<JoinHandle<()>>,
+}
+#[cfg(unix)]
+impl SilentDaemon {
+    pub fn start() -> io::Result<Self> {
+        let socket = temp_socket("silent");
+        let listener = UnixListener::bind(&socket)?;
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let server = {
+            let stopped = stopped.clone();
+            std::thread::spawn(move || {
+                let mut connections = vec![];
+                for stream in listener.incoming() {
+                    if stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    connections.extend(stream);
+                }
+            })
+        };
+
+        Ok(Self {
+            socket,
+            stopped,
+            server: Some(server),
+        })
+    }
+
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// A client which talks to this daemon, pinned to `MAX_API_VERSION`
+    /// since the daemon would never answer the negotiating ping.
+    pub fn client(&self) -> DockerClient {
+        DockerClient::new("localhost", false)
+            .with_unix_socket(&self.socket)
+            .with_api_version(MAX_API_VERSION)
+    }
+}
+#[cfg(unix)]
+impl Drop for SilentDaemon {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag.
+        let _ = UnixStream::connect(&self.socket);
+        if let Some(server) = self.server.take() {
+            let _ = server.join();
+        }
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+/// A transport which answers requests in memory from scripted routes, the
+/// way `MockDaemon` does over its socket. Clones share routes and requests,
+/// so one can be kept to inspect what a client sent.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<State>>,
+}
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers every `method` request to `path` with `response`, as
+    /// `MockDaemon::route` does.
+    pub fn route(&self, method: &str, path: &str, response: MockResponse) -> &Self {
+        State::add(&self.state, method, path, response, false);
+        self
+    }
+
+    /// Answers the next `method` request to `path` with `response`, as
+    /// `MockDaemon::route_once` does.
+    pub fn route_once(&self, method: &str, path: &str, response: MockResponse) -> &Self {
+        State::add(&self.state, method, path, response, true);
+        self
+    }
+
+    /// The requests sent so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// A client which sends its requests over this transport.
+    pub fn client(&self) -> DockerClient {
+        DockerClient::new("localhost", false).with_transport(self.clone())
+    }
+}
+impl Transport for MockTransport {
+    fn send(
+        &self,
+        request: &HttpRequest<'_>,
+        response: &mut dyn ResponseSink,
+    ) -> DockerResult<u32> {
+        let (path, query) = match request.path.split_once('?') {
+            Some((path, query)) => (path, Some(query.to_string())),
+            None => (request.path, None),
+        };
+        let mut headers = vec![("Host".to_string(), request.host.to_string())];
+        if let Some(content_type) = request.content_type {
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+        }
+        if request.method == "POST" || !request.body.is_empty() {
+            headers.push(("Content-Length".to_string(), request.body.len().to_string()));
+        }
+
+        let answer = respond(
+            &self.state,
+            RecordedRequest {
+                method: request.method.to_string(),
+                path: path.to_string(),
+                query,
+                api_version: request.api_version.map(str::to_string),
+                headers,
+                body: request.body.to_vec(),
+            },
+        );
+        let mut head = answer.head();
+        head.push("\r\n".to_string());
+        for line in &head {
+            if !response.header(line.as_bytes()) {
+                return Err(request.aborted());
+            }
+        }
+        for chunk in &answer.chunks {
+            if !response.write(chunk) {
+                return Err(request.aborted());
+            }
+        }
+        Ok(answer.status)
+    }
+}
+
+/// A fresh socket path in the temporary directory.
+#[cfg(unix)]
+fn temp_socket(kind: &str) -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let socket = std::env::temp_dir().join(format!(
+        "dockurl-{}-{}-{}.sock",
+        kind,
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = std::fs::remove_file(&socket);
+    socket
+}
+
+/// Answers the requests on one connection until the client closes it.
+#[cfg(unix)]
+fn serve(stream: UnixStream, state: &Mutex<State>) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    let mut reader = BufReader::new(stream);
+    while let Ok(Some(request)) = read_request(&mut reader, &mut writer) {
+        let response = respond(state, request);
+        if response.write_to(&mut writer).is_err() {
+            return;
+        }
+    }
+}
+
+fn respond(state: &Mutex<State>, request: RecordedRequest) -> MockResponse {
+    let mut state = state.lock().unwrap();
+    let response = match state
+        .routes
+        .iter()
+        .position(|route| route.matches(&request.method, &request.path))
+    {
+        Some(index) if state.routes[index].once => state.routes.remove(index).response,
+        Some(index) => state.routes[index].response.clone(),
+        None if request.method == "GET" && request.path == "/_ping" => {
+            return MockResponse::new(200)
+                .header("API-Version", MAX_API_VERSION)
+                .body(b"OK");
+        }
+        None => MockResponse::json(
+            404,
+            &serde_json::json!({
+                "message": format!("page not found: {} {}", request.method, request.path)
+            })
+            .to_string(),
+        ),
+    };
+    state.requests.push(request);
+    response
+}
+
+/// Reads one request, or `None` once the client has closed the connection.
+#[cfg(unix)]
+fn read_request(
+    reader: &mut BufReader<UnixStream>,
+    writer: &mut UnixStream,
+) -> io::Result<Option<RecordedRequest>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, Some(query.to_string())),
+        None => (target, None),
+    };
+    let (api_version, path) = split_version(path);
+
+    let mut headers = vec![];
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+    };
+
+    if header("Expect").is_some_and(|expect| expect.eq_ignore_ascii_case("100-continue")) {
+        writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+    }
+    let length = header("Content-Length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Some(RecordedRequest {
+        method,
+        path,
+        query,
+        api_version,
+        headers,
+        body,
+    }))
+}
+
+/// Splits a `/v1.41` prefix off `path`.
+#[cfg(unix)]
+fn split_version(path: &str) -> (Option<String>, String) {
+    let mut segments = path.splitn(3, '/').skip(1);
+    match (segments.next(), segments.next()) {
+        (Some(version), rest)
+            if version.len() > 1
+                && version.starts_with('v')
+                && version[1..].chars().all(|c| c.is_ascii_digit() || c == '.') =>
+        {
+            (
+                Some(version[1..].to_string()),
+                format!("/{}", rest.unwrap_or_default()),
+            )
+        }
+        _ => (None, path.to_string()),
+    }
+}
+
+fn reason(status: u32) -> &'static str {
+    match status {
+        101 => "UPGRADED",
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    use crate::container::create::options::Options;
+    #[cfg(unix)]
+    use crate::error::Operation;
+    #[cfg(unix)]
+    use crate::network::NetworkMode;
+    use crate::output::Discard;
+    #[cfg(unix)]
+    use crate::testing::{split_version, MockDaemon};
+    use crate::testing::{MockResponse, MockTransport};
+    #[cfg(unix)]
+    use std::path::Path;
+
+    #[test]
+    #[cfg(unix)]
+    fn splits_the_api_version_prefix() {
+        assert_eq!(
+            split_version("/v1.41/containers/json"),
+            (Some("1.41".to_string()), "/containers/json".to_string())
+        );
+        assert_eq!(split_version("/_ping"), (None, "/_ping".to_string()));
+        assert_eq!(
+            split_version("/volumes/x"),
+            (None, "/volumes/x".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn records_requests_and_answers_from_routes() {
+        let daemon = MockDaemon::start().unwrap();
+        daemon.route(
+            "POST",
+            "/containers/create",
+            MockResponse::json(201, r#"{"Id":"4fa6e0f0c6786287e131c3852c58a2e01cc697a68231826813597e4994f1d6e2","Warnings":[]}"#),
+        );
+
+        let mut options = Options::new();
+        options.image("alpine");
+        let client = daemon.client().with_api_version("1.41");
+        assert_eq!(
+            client.create_container(options, Discard).unwrap(),
+            "4fa6e0f0c678"
+        );
+
+        let requests = daemon.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/containers/create");
+        assert_eq!(requests[0].api_version.as_deref(), Some("1.41"));
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["Image"], "alpine");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn surfaces_daemon_errors() {
+        let daemon = MockDaemon::start().unwrap();
+        daemon
+            .route(
+                "DELETE",
+                "/containers/*",
+                MockResponse::json(409, r#"{"message":"container is running"}"#),
+            )
+            .route(
+                "POST",
+                "/networks/create",
+                MockResponse::json(500, r#"{"message":"pool overlaps"}"#),
+            )
+            .route(
+                "POST",
+                "/containers/*/kill",
+                MockResponse::new(500).body(b"not json"),
+            );
+        let client = daemon.client();
+
+        let error = client
+            .delete_container("abc", Discard, false, false, false)
+            .unwrap_err();
+        assert!(error.is_conflict());
+        assert_eq!(error.api_error().unwrap().message, "container is running");
+
+        let error = client
+            .create_network("bench", NetworkMode::Bridge, Discard)
+            .unwrap_err();
+        assert!(error.is_server_error());
+        assert_eq!(
+            error.api_error().unwrap().operation,
+            Operation::NetworkCreate
+        );
+
+        let error = client.kill_container("abc", Discard).unwrap_err();
+        assert_eq!(error.api_error().unwrap().message, "not json");
+
+        let error = client.inspect_network("missing", Discard).unwrap_err();
+        assert!(error.is_not_found());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn streams_chunked_progress() {
+        let daemon = MockDaemon::start().unwrap();
+        daemon.route(
+            "POST",
+            "/build",
+            MockResponse::stream(
+                200,
+                &[
+                    "{\"stream\":\"Step 1/1 : FROM alpine\\n\"}\r\n{\"au",
+                    "x\":{\"ID\":\"sha256:e821df6f41ad\"}}\r\n",
+                ],
+            ),
+        );
+
+        let context = std::env::temp_dir().join(format!("dockurl-build-{}", std::process::id()));
+        std::fs::create_dir_all(&context).unwrap();
+        let image_id = daemon
+            .client()
+            .build_image("bench:latest", Path::new("Dockerfile"), &context, Discard)
+            .unwrap();
+        std::fs::remove_dir_all(context).unwrap();
+
+        assert_eq!(image_id, "e821df6f41ad");
+        let requests = daemon.requests();
+        assert_eq!(
+            requests[0].query.as_deref(),
+            Some("dockerfile=Dockerfile&t=bench%3Alatest")
+        );
+        assert_eq!(
+            requests[0].header("content-type"),
+            Some("application/x-tar")
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn once_routes_script_a_sequence() {
+        let daemon = MockDaemon::start().unwrap();
+        daemon
+            .route_once("POST", "/containers/*/stop", MockResponse::new(204))
+            .route("POST", "/containers/*/stop", MockResponse::new(304))
+            .route_once("POST", "/containers/*/stop", MockResponse::new(500));
+
+        let client = daemon.client();
+        client.stop_container("abc", Discard).unwrap();
+        client.stop_container("abc", Discard).unwrap();
+        client.stop_container("abc", Discard).unwrap();
+        assert_eq!(daemon.requests().len(), 3);
+    }
+
+    #[test]
+    fn mock_transport_answers_in_memory() {
+        let transport = MockTransport::new();
+        transport
+            .route(
+                "GET",
+                "/_ping",
+                MockResponse::new(200)
+                    .header("API-Version", "1.41")
+                    .body(b"OK"),
+            )
+            .route(
+                "POST",
+                "/images/create",
+                MockResponse::stream(
+                    200,
+                    &[
+                        "{\"status\":\"Pulling fs layer\"}\r\n{\"error\":\"di",
+                        "sk full\"}\r\n",
+                    ],
+                ),
+            );
+
+        let client = transport.client();
+        assert_eq!(client.negotiate_api_version().unwrap(), "1.41");
+        let error = client
+            .create_image("alpine", "latest", Discard)
+            .unwrap_err();
+        assert_eq!(error.api_error().unwrap().message, "disk full");
+        let error = client.stop_container("abc", Discard).unwrap_err();
+        assert!(error.is_not_found());
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].api_version, None);
+        assert_eq!(requests[1].api_version.as_deref(), Some("1.41"));
+        assert_eq!(
+            requests[1].query.as_deref(),
+            Some("fromImage=alpine&tag=latest")
+        );
+        assert_eq!(requests[2].path, "/containers/abc/stop");
+    }
+}